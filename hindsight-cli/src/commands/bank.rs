@@ -1,9 +1,53 @@
-use anyhow::Result;
-use crate::api::ApiClient;
+use anyhow::{Context, Result};
+use crate::api::{types, ApiClient, BankSummary, DateRange, Verbosity};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
-pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> Result<()> {
+/// Client-side narrowing for `bank list --filter/--prefix`; both are case-insensitive
+#[derive(Debug, Default)]
+pub struct BankFilter {
+    /// Substring of the bank ID or name
+    pub contains: Option<String>,
+    /// Start of the bank ID
+    pub prefix: Option<String>,
+}
+
+impl BankFilter {
+    fn is_active(&self) -> bool {
+        self.contains.is_some() || self.prefix.is_some()
+    }
+
+    fn matches(&self, bank: &types::BankListItem) -> bool {
+        let bank_id = bank.bank_id.to_lowercase();
+        let prefix_ok = self.prefix.as_ref().is_none_or(|prefix| bank_id.starts_with(&prefix.to_lowercase()));
+        let contains_ok = self.contains.as_ref().is_none_or(|needle| {
+            let needle = needle.to_lowercase();
+            bank_id.contains(&needle) || bank.name.as_ref().is_some_and(|name| name.to_lowercase().contains(&needle))
+        });
+        prefix_ok && contains_ok
+    }
+
+    // Helper function for the "Found ..." header: the count, plus what it was narrowed from
+    fn header(&self, shown: usize, total: usize) -> String {
+        if !self.is_active() {
+            return format!("Found {} bank(s)", shown);
+        }
+        let mut criteria = Vec::new();
+        if let Some(prefix) = &self.prefix {
+            criteria.push(format!("starting with '{}'", prefix));
+        }
+        if let Some(needle) = &self.contains {
+            criteria.push(format!("containing '{}'", needle));
+        }
+        format!("Found {} of {} bank(s) {}", shown, total, criteria.join(" and "))
+    }
+}
+
+pub fn list(client: &ApiClient, detailed: bool, filter: &BankFilter, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
+    if detailed {
+        return list_detailed(client, filter, verbose, output_format);
+    }
+
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Fetching banks..."))
     } else {
@@ -17,12 +61,14 @@ pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> R
     }
 
     match response {
-        Ok(banks_list) => {
+        Ok(mut banks_list) => {
+            let total = banks_list.len();
+            banks_list.retain(|bank| filter.matches(bank));
             if output_format == OutputFormat::Pretty {
                 if banks_list.is_empty() {
-                    ui::print_warning("No banks found");
+                    ui::print_warning(if filter.is_active() { "No banks match the filter" } else { "No banks found" });
                 } else {
-                    ui::print_info(&format!("Found {} bank(s)", banks_list.len()));
+                    ui::print_info(&filter.header(banks_list.len(), total));
                     for bank in &banks_list {
                         println!("  - {}", bank.bank_id);
                     }
@@ -36,7 +82,54 @@ pub fn list(client: &ApiClient, verbose: bool, output_format: OutputFormat) -> R
     }
 }
 
-pub fn disposition(client: &ApiClient, bank_id: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
+// Helper function for `bank list --detailed`. The list endpoint has no counts, so
+// each bank's stats are fetched as well (only for banks that pass the filter).
+fn list_detailed(client: &ApiClient, filter: &BankFilter, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Fetching banks and their statistics..."))
+    } else {
+        None
+    };
+
+    let mut total = 0;
+    let response = client.list_agents(verbose).and_then(|banks| {
+        total = banks.len();
+        banks
+            .into_iter()
+            .filter(|bank| filter.matches(bank))
+            .map(|bank| {
+                let stats = client
+                    .get_stats(&bank.bank_id, verbose)
+                    .with_context(|| format!("Failed to fetch statistics for bank {}", bank.bank_id))?;
+                Ok(BankSummary {
+                    bank,
+                    memory_count: stats.total_nodes,
+                    document_count: stats.total_documents,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    });
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let banks = response?;
+    if output_format == OutputFormat::Pretty {
+        if banks.is_empty() {
+            ui::print_warning(if filter.is_active() { "No banks match the filter" } else { "No banks found" });
+        } else {
+            ui::print_info(&filter.header(banks.len(), total));
+            println!();
+            ui::print_banks_table(&banks);
+        }
+    } else {
+        output::print_output(&banks, output_format)?;
+    }
+    Ok(())
+}
+
+pub fn disposition(client: &ApiClient, bank_id: &str, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Fetching disposition..."))
     } else {
@@ -62,14 +155,48 @@ pub fn disposition(client: &ApiClient, bank_id: &str, verbose: bool, output_form
     }
 }
 
-pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
+// Helper function to find the earliest and latest memory dates in an export.
+// Uses `occurred_start`, falling back to `date`; unparseable or missing dates count as undated.
+fn date_range_of(memories: &[serde_json::Map<String, serde_json::Value>]) -> DateRange {
+    let mut range = DateRange::default();
+    let mut earliest: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+    let mut latest: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+
+    for memory in memories {
+        let date = ["occurred_start", "date"]
+            .iter()
+            .filter_map(|key| memory.get(*key).and_then(|v| v.as_str()))
+            .find_map(|value| chrono::DateTime::parse_from_rfc3339(value).ok());
+
+        match date {
+            Some(date) => {
+                earliest = Some(earliest.map_or(date, |e| e.min(date)));
+                latest = Some(latest.map_or(date, |l| l.max(date)));
+            }
+            None => range.undated += 1,
+        }
+    }
+
+    range.earliest = earliest.map(|d| d.to_rfc3339());
+    range.latest = latest.map(|d| d.to_rfc3339());
+    range
+}
+
+pub fn stats(client: &ApiClient, bank_id: &str, date_range: bool, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Fetching statistics..."))
     } else {
         None
     };
 
-    let response = client.get_stats(bank_id, verbose);
+    let response = client.get_stats(bank_id, verbose).and_then(|mut stats| {
+        if date_range {
+            // The API has no date aggregate, so scan every memory
+            let memories = client.export_memories(bank_id, verbose, |_, _| {})?;
+            stats.date_range = Some(date_range_of(&memories));
+        }
+        Ok(stats)
+    });
 
     if let Some(mut sp) = spinner {
         sp.finish();
@@ -127,6 +254,16 @@ pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: Ou
                     println!();
                 }
 
+                if let Some(range) = &stats.date_range {
+                    println!("{}", ui::gradient_text("─── Dates ───"));
+                    println!("  {:<10} {}", "earliest", range.earliest.as_deref().unwrap_or("-"));
+                    println!("  {:<10} {}", "latest", range.latest.as_deref().unwrap_or("-"));
+                    if range.undated > 0 {
+                        println!("  {:<10} {}", "undated", range.undated);
+                    }
+                    println!();
+                }
+
                 if stats.pending_operations > 0 || stats.failed_operations > 0 {
                     println!("{}", ui::gradient_text("─── Operations ───"));
                     if stats.pending_operations > 0 {
@@ -145,7 +282,7 @@ pub fn stats(client: &ApiClient, bank_id: &str, verbose: bool, output_format: Ou
     }
 }
 
-pub fn update_name(client: &ApiClient, bank_id: &str, name: &str, verbose: bool, output_format: OutputFormat) -> Result<()> {
+pub fn update_name(client: &ApiClient, bank_id: &str, name: &str, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Updating bank name..."))
     } else {
@@ -176,7 +313,7 @@ pub fn update_background(
     bank_id: &str,
     content: &str,
     no_update_disposition: bool,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat
 ) -> Result<()> {
     let current_profile = if !no_update_disposition {
@@ -226,7 +363,7 @@ pub fn delete(
     client: &ApiClient,
     bank_id: &str,
     yes: bool,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat
 ) -> Result<()> {
     // Confirmation prompt unless -y flag is used
@@ -275,3 +412,130 @@ pub fn delete(
         Err(e) => Err(e)
     }
 }
+
+/// Warn about any bank in `bank_ids` (comma-separated for recall) that the server
+/// doesn't know, suggesting the closest existing one. Used by `--check-bank`.
+pub fn check_known(client: &ApiClient, bank_ids: &str, verbose: Verbosity, output_format: OutputFormat) -> Result<()> {
+    let known: Vec<String> = client
+        .list_agents(verbose)
+        .context("Failed to list banks for --check-bank")?
+        .into_iter()
+        .map(|bank| bank.bank_id)
+        .collect();
+    for bank_id in bank_ids.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        if known.iter().any(|id| id == bank_id) {
+            continue;
+        }
+        let message = match closest_bank(bank_id, &known) {
+            Some(suggestion) => format!("Bank '{}' not found; did you mean '{}'?", bank_id, suggestion),
+            None => format!("Bank '{}' not found (see `hindsight bank list`)", bank_id),
+        };
+        if output_format == OutputFormat::Pretty {
+            ui::print_warning(&message);
+        } else {
+            eprintln!("warning: {}", message);
+        }
+    }
+    Ok(())
+}
+
+// Helper function to pick the known bank nearest to a mistyped one. A case-only
+// difference always wins; otherwise the edit distance must be small for the ID's length.
+fn closest_bank<'a>(bank_id: &str, known: &'a [String]) -> Option<&'a str> {
+    let lower = bank_id.to_lowercase();
+    let max_distance = (lower.chars().count() / 3).max(1);
+    known
+        .iter()
+        .map(|id| (levenshtein(&lower, &id.to_lowercase()), id))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, id)| id.as_str())
+}
+
+// Helper function for the number of single-character edits between two strings
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(json: &str) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_date_range_of() {
+        let memories = vec![
+            memory(r#"{"occurred_start": "2024-03-05T10:00:00+00:00", "date": "2024-01-01T00:00:00+00:00"}"#),
+            memory(r#"{"occurred_start": null, "date": "2023-12-24T09:30:00+00:00"}"#),
+            memory(r#"{"occurred_start": "2024-06-01T00:00:00+00:00"}"#),
+            memory(r#"{"date": ""}"#),
+        ];
+        let range = date_range_of(&memories);
+        assert_eq!(range.earliest.as_deref(), Some("2023-12-24T09:30:00+00:00"));
+        assert_eq!(range.latest.as_deref(), Some("2024-06-01T00:00:00+00:00"));
+        assert_eq!(range.undated, 1);
+    }
+
+    #[test]
+    fn test_date_range_of_empty() {
+        assert_eq!(date_range_of(&[]), DateRange::default());
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("notes", "notes"), 0);
+        assert_eq!(levenshtein("notes", "note"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest_bank() {
+        let known = vec!["Notes".to_string(), "team-engineering".to_string(), "work".to_string()];
+        assert_eq!(closest_bank("notes", &known), Some("Notes"));
+        assert_eq!(closest_bank("team-enginering", &known), Some("team-engineering"));
+        assert_eq!(closest_bank("wrok", &known), None);
+        assert_eq!(closest_bank("personal", &known), None);
+    }
+
+    fn bank(bank_id: &str, name: Option<&str>) -> types::BankListItem {
+        serde_json::from_value(serde_json::json!({
+            "bank_id": bank_id,
+            "name": name,
+            "disposition": { "skepticism": 3, "literalism": 3, "empathy": 3 },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bank_filter() {
+        let banks = [bank("test-alice", None), bank("Test-bob", Some("Bob")), bank("notes", Some("Alice's notes"))];
+        let ids = |filter: &BankFilter| -> Vec<String> {
+            banks.iter().filter(|b| filter.matches(b)).map(|b| b.bank_id.clone()).collect()
+        };
+
+        assert_eq!(ids(&BankFilter::default()).len(), 3);
+        let prefix = BankFilter { prefix: Some("test-".to_string()), ..Default::default() };
+        assert_eq!(ids(&prefix), vec!["test-alice", "Test-bob"]);
+        // --filter also looks at the name
+        let contains = BankFilter { contains: Some("ALICE".to_string()), ..Default::default() };
+        assert_eq!(ids(&contains), vec!["test-alice", "notes"]);
+        let both = BankFilter { contains: Some("alice".to_string()), prefix: Some("test".to_string()) };
+        assert_eq!(ids(&both), vec!["test-alice"]);
+        assert_eq!(both.header(1, 3), "Found 1 of 3 bank(s) starting with 'test' and containing 'alice'");
+    }
+}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+use crate::output::{self, OutputFormat};
+use crate::recall_cache;
+use crate::ui;
+
+pub fn clear(output_format: OutputFormat) -> Result<()> {
+    let dir = recall_cache::cache_dir().ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+    let removed = recall_cache::clear(&dir)?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Cleared {} cached recall results", removed));
+    } else {
+        output::print_output(&serde_json::json!({ "entries_removed": removed }), output_format)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use crate::config::{self, Config, ConfigOverrides};
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+// Helper function to resolve the config file path: --config, else ~/.hindsight/config
+fn config_path(overrides: &ConfigOverrides) -> Result<PathBuf> {
+    overrides
+        .config_path
+        .clone()
+        .or_else(Config::config_file_path)
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))
+}
+
+pub fn init(overrides: &ConfigOverrides, force: bool, output_format: OutputFormat) -> Result<()> {
+    let path = config_path(overrides)?;
+    Config::write_template(&path, force)?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Wrote starter config to {}", path.display()));
+        println!("  Edit it to set your API URL, API key and profiles.");
+    } else {
+        let result = serde_json::json!({
+            "config_path": path.display().to_string(),
+        });
+        output::print_output(&result, output_format)?;
+    }
+
+    Ok(())
+}
+
+pub fn show(overrides: &ConfigOverrides, output_format: OutputFormat) -> Result<()> {
+    let config = Config::load_with(overrides)?;
+    let path = config_path(overrides)?;
+    let api_key = config.api_key.as_deref().map(config::mask_api_key);
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_info("Effective configuration");
+        println!();
+        println!("  API URL: {} (from {})", config.api_url, config.source);
+        println!("  API Key: {}", api_key.as_deref().unwrap_or("not set"));
+        if let Some(profile) = &config.profile {
+            println!("  Profile: {}", profile);
+        }
+        if let Some(default_bank) = &config.default_bank {
+            println!("  Default bank: {}", default_bank);
+        }
+        if let Some(ca_cert) = &config.ca_cert {
+            println!("  CA certificate: {}", ca_cert.display());
+        }
+        let status = if path.exists() { "" } else { " (not found)" };
+        println!("  Config file: {}{}", path.display(), status);
+    } else {
+        let result = serde_json::json!({
+            "api_url": config.api_url,
+            "source": config.source.to_string(),
+            "api_key": api_key,
+            "profile": config.profile,
+            "ca_cert": config.ca_cert,
+            "default_bank": config.default_bank,
+            "config_path": path.display().to_string(),
+            "config_file_exists": path.exists(),
+        });
+        output::print_output(&result, output_format)?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use crate::api::ApiClient;
+use serde::Serialize;
+use crate::api::{ApiClient, Verbosity};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
@@ -9,7 +10,7 @@ pub fn list(
     query: Option<String>,
     limit: i32,
     offset: i32,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -54,7 +55,7 @@ pub fn get(
     client: &ApiClient,
     agent_id: &str,
     document_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -91,7 +92,7 @@ pub fn delete(
     client: &ApiClient,
     agent_id: &str,
     document_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -122,3 +123,191 @@ pub fn delete(
         Err(e) => Err(e)
     }
 }
+
+/// Which documents `document delete --prefix/--glob` removes
+pub enum DocumentMatch {
+    Prefix(String),
+    Glob(String),
+}
+
+impl DocumentMatch {
+    fn matches(&self, document_id: &str) -> bool {
+        match self {
+            DocumentMatch::Prefix(prefix) => document_id.starts_with(prefix.as_str()),
+            DocumentMatch::Glob(pattern) => glob_match(pattern, document_id),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            DocumentMatch::Prefix(prefix) => format!("prefix '{}'", prefix),
+            DocumentMatch::Glob(pattern) => format!("pattern '{}'", pattern),
+        }
+    }
+}
+
+/// Shell-style match where `*` is any run of characters and `?` is exactly one
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is currently standing in for
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and try again
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[derive(Debug, Serialize)]
+struct BulkDeleteResult {
+    documents_deleted: usize,
+    memory_units_deleted: i64,
+    document_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<String>,
+}
+
+/// Most matching IDs listed before asking for confirmation
+const MAX_LISTED_DOCUMENTS: usize = 20;
+
+pub fn delete_matching(
+    client: &ApiClient,
+    agent_id: &str,
+    matcher: &DocumentMatch,
+    yes: bool,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Finding matching documents..."))
+    } else {
+        None
+    };
+
+    let response = client.list_all_document_ids(agent_id, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let matching: Vec<String> = response?.into_iter().filter(|id| matcher.matches(id)).collect();
+
+    if matching.is_empty() {
+        if output_format == OutputFormat::Pretty {
+            ui::print_info(&format!("No documents in bank '{}' match {}", agent_id, matcher.describe()));
+        } else {
+            let result = BulkDeleteResult {
+                documents_deleted: 0,
+                memory_units_deleted: 0,
+                document_ids: Vec::new(),
+                failures: Vec::new(),
+            };
+            output::print_output(&result, output_format)?;
+        }
+        return Ok(());
+    }
+
+    // Confirmation prompt unless -y flag is used
+    if !yes && output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("{} documents match {}:", matching.len(), matcher.describe()));
+        for id in matching.iter().take(MAX_LISTED_DOCUMENTS) {
+            println!("  {}", id);
+        }
+        if matching.len() > MAX_LISTED_DOCUMENTS {
+            println!("  ... and {} more", matching.len() - MAX_LISTED_DOCUMENTS);
+        }
+
+        let message = format!(
+            "Delete these {} documents and all their memory units from bank '{}'? This cannot be undone.",
+            matching.len(),
+            agent_id
+        );
+        if !ui::prompt_confirmation(&message)? {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let pb = ui::create_progress_bar(matching.len() as u64, "Deleting documents");
+
+    let mut deleted = Vec::new();
+    let mut memory_units_deleted = 0;
+    let mut failures = Vec::new();
+    for id in &matching {
+        match client.delete_document(agent_id, id, verbose) {
+            Ok(result) => {
+                memory_units_deleted += result.deleted_count.unwrap_or(0);
+                deleted.push(id.clone());
+            }
+            Err(e) => failures.push(format!("{}: {:#}", id, e)),
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!(
+            "Deleted {} documents ({} memory units) from bank '{}'",
+            deleted.len(),
+            memory_units_deleted,
+            agent_id
+        ));
+    } else {
+        let result = BulkDeleteResult {
+            documents_deleted: deleted.len(),
+            memory_units_deleted,
+            document_ids: deleted,
+            failures: failures.clone(),
+        };
+        output::print_output(&result, output_format)?;
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            ui::print_error(failure);
+        }
+        anyhow::bail!("{} of {} documents could not be deleted", failures.len(), matching.len());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("import-2024-*", "import-2024-03-notes.md"));
+        assert!(glob_match("*.md", "notes/today.md"));
+        assert!(glob_match("doc-?", "doc-1"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXXbYYbc"));
+        assert!(!glob_match("doc-?", "doc-12"));
+        assert!(!glob_match("*.md", "notes.txt"));
+        assert!(!glob_match("import-*", "reimport-1"));
+    }
+
+    #[test]
+    fn test_document_match() {
+        let prefix = DocumentMatch::Prefix("batch-7/".to_string());
+        assert!(prefix.matches("batch-7/a.txt"));
+        assert!(!prefix.matches("batch-70/a.txt"));
+        assert!(DocumentMatch::Glob("batch-?/*".to_string()).matches("batch-7/a.txt"));
+    }
+}
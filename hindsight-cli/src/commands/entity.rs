@@ -1,5 +1,5 @@
 use anyhow::Result;
-use crate::api::ApiClient;
+use crate::api::{ApiClient, Verbosity};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
@@ -7,7 +7,7 @@ pub fn list(
     client: &ApiClient,
     bank_id: &str,
     limit: i64,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -55,7 +55,7 @@ pub fn get(
     client: &ApiClient,
     bank_id: &str,
     entity_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -96,7 +96,7 @@ pub fn regenerate(
     client: &ApiClient,
     bank_id: &str,
     entity_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
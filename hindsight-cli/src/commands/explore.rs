@@ -1,4 +1,4 @@
-use crate::api::{ApiClient, RecallRequest, ReflectRequest};
+use crate::api::{ApiClient, RecallRequest, ReflectRequest, Verbosity};
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
@@ -234,7 +234,7 @@ impl App {
     }
 
     fn load_banks(&mut self) -> Result<()> {
-        self.banks = self.client.list_agents(false)?;
+        self.banks = self.client.list_agents(Verbosity::default())?;
 
         if !self.banks.is_empty() && self.banks_state.selected().is_none() {
             self.banks_state.select(Some(0));
@@ -251,7 +251,7 @@ impl App {
             None,
             Some(self.memories_limit),
             Some(self.memories_offset),
-            false
+            Verbosity::default()
         )?;
         self.memories = response.items;
 
@@ -283,7 +283,7 @@ impl App {
     }
 
     fn load_entities(&mut self, bank_id: &str) -> Result<()> {
-        let response = self.client.list_entities(bank_id, Some(100), None, false)?;
+        let response = self.client.list_entities(bank_id, Some(100), None, Verbosity::default())?;
         self.entities = response.items;
 
         if !self.entities.is_empty() && self.entities_state.selected().is_none() {
@@ -295,7 +295,7 @@ impl App {
     }
 
     fn load_documents(&mut self, bank_id: &str) -> Result<()> {
-        let response = self.client.list_documents(bank_id, None, Some(100), Some(0), false)?;
+        let response = self.client.list_documents(bank_id, None, Some(100), Some(0), Verbosity::default())?;
         self.documents = response.items;
 
         if !self.documents.is_empty() && self.documents_state.selected().is_none() {
@@ -345,7 +345,7 @@ impl App {
                             tags_match: TagsMatch::Any,
                         };
 
-                        let result = client.recall(&bank_id, &request, false)
+                        let result = client.recall(&bank_id, &request, Verbosity::default())
                             .map(|r| r.results)
                             .map_err(|e| e.to_string());
 
@@ -363,7 +363,7 @@ impl App {
                             tags_match: TagsMatch::Any,
                         };
 
-                        let result = client.reflect(&bank_id, &request, false)
+                        let result = client.reflect(&bank_id, &request, Verbosity::default())
                             .map(|r| r.text)
                             .map_err(|e| e.to_string());
 
@@ -637,7 +637,7 @@ impl App {
                             .unwrap_or("");
 
                         if !doc_id.is_empty() {
-                            match self.client.get_document(bank_id, doc_id, false) {
+                            match self.client.get_document(bank_id, doc_id, Verbosity::default()) {
                                 Ok(full_doc) => {
                                     // Convert to Map for display
                                     let doc_map: Map<String, Value> = serde_json::from_value(
@@ -717,7 +717,7 @@ impl App {
                         .unwrap_or("");
 
                     if !doc_id.is_empty() {
-                        match self.client.delete_document(bank_id, doc_id, false) {
+                        match self.client.delete_document(bank_id, doc_id, Verbosity::default()) {
                             Ok(_) => {
                                 self.status_message = format!("Deleted document: {}", doc_id);
                                 self.refresh()?;
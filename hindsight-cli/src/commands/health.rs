@@ -0,0 +1,50 @@
+use anyhow::Result;
+use crate::api::{ApiClient, Verbosity};
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+pub fn check(
+    client: &ApiClient,
+    api_url: &str,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Checking server health..."))
+    } else {
+        None
+    };
+
+    let response = client.health(verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    let health = response?;
+
+    if output_format == OutputFormat::Pretty {
+        if health.is_healthy() {
+            ui::print_success(&format!("Server at {} is healthy", api_url));
+        } else {
+            ui::print_error(&format!("Server at {} is {}", api_url, health.status));
+        }
+        if let Some(database) = &health.database {
+            println!("  Database: {}", database);
+        }
+        if let Some(reason) = &health.reason {
+            println!("  Reason: {}", reason);
+        }
+        if let Some(error) = &health.error {
+            println!("  Error: {}", error);
+        }
+    } else {
+        output::print_output(&health, output_format)?;
+    }
+
+    if !health.is_healthy() {
+        anyhow::bail!("Server reported status '{}'", health.status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use colored::*;
+use crate::history::{self, HistoryEntry};
+use crate::output::{self, OutputFormat};
+use crate::ui;
+
+// Helper function to resolve the history file, which lives in the user's data directory
+fn history_path() -> Result<std::path::PathBuf> {
+    history::history_file_path().ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))
+}
+
+pub fn list(limit: usize, output_format: OutputFormat) -> Result<()> {
+    let entries = history::load(&history_path()?)?;
+    // Most recent first
+    let recent: Vec<HistoryEntry> = entries.into_iter().rev().take(limit).collect();
+
+    if output_format == OutputFormat::Pretty {
+        if recent.is_empty() {
+            ui::print_info("No queries in history yet");
+            return Ok(());
+        }
+        ui::print_info(&format!("Recent queries ({})", recent.len()));
+        println!();
+        let header = format!("  {:<20}  {:<8}  {:<20}  {:>7}  {}", "WHEN", "COMMAND", "BANK", "RESULTS", "QUERY");
+        println!("{}", header.bright_black());
+        for entry in &recent {
+            let when = ui::format_fact_date(&entry.timestamp);
+            let count = entry.result_count.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string());
+            println!(
+                "  {:<20}  {:<8}  {:<20}  {:>7}  {}",
+                when, entry.command, entry.bank_id, count, entry.query
+            );
+        }
+    } else {
+        output::print_output(&recent, output_format)?;
+    }
+
+    Ok(())
+}
+
+pub fn clear(yes: bool, output_format: OutputFormat) -> Result<()> {
+    let path = history_path()?;
+
+    // Confirmation prompt unless -y flag is used
+    if !yes && output_format == OutputFormat::Pretty {
+        let confirmed = ui::prompt_confirmation("Clear your local query history?")?;
+
+        if !confirmed {
+            ui::print_info("Operation cancelled");
+            return Ok(());
+        }
+    }
+
+    let removed = history::clear(&path)?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Cleared {} history entries", removed));
+    } else {
+        output::print_output(&serde_json::json!({ "entries_removed": removed }), output_format)?;
+    }
+
+    Ok(())
+}
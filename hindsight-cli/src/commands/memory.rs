@@ -1,11 +1,24 @@
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use indicatif::ProgressBar;
 use walkdir::WalkDir;
 
-use crate::api::{ApiClient, RecallRequest, ReflectRequest, MemoryItem, RetainRequest};
+use crate::api::{ApiClient, BankRecallResult, MemoryPutResult, MultiBankRecallResponse, QueryRecallResult, RecallRequest, RecallResponse, ReflectRequest, MemoryItem, RetainRequest, Verbosity};
 use crate::config;
+use crate::conversation;
+use crate::errors::{InterruptedError, NoResultsError};
+use crate::history;
+use crate::interrupt;
+use crate::manifest::{self, Manifest};
 use crate::output::{self, OutputFormat};
+use crate::pager;
+use crate::recall_cache;
+use crate::sort::{self, AsRecallResult, SortBy};
 use crate::ui;
 
 // Import types from generated client
@@ -17,34 +30,623 @@ fn parse_budget(budget: &str) -> Budget {
     match budget.to_lowercase().as_str() {
         "low" => Budget::Low,
         "high" => Budget::High,
-        _ => Budget::Mid, // Default to mid
+        _ => Budget::Mid, // clap only lets low, mid and high through
     }
 }
 
-// Helper function to check if a file has a text-based extension
-fn is_text_file(path: &std::path::Path) -> bool {
-    const TEXT_EXTENSIONS: &[&str] = &[
-        "txt", "md", "json", "yaml", "yml", "toml", "xml", "csv", "log", "rst", "adoc",
-    ];
+/// Upper bound for --max-tokens and --chunk-max-tokens: a loose sanity check that
+/// catches typos such as extra digits before a request is sent
+pub const MAX_TOKENS_LIMIT: i64 = 1_000_000;
+
+/// File extensions collected by `retain-files` when `--ext` is not given
+pub const DEFAULT_TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "json", "yaml", "yml", "toml", "xml", "csv", "log", "rst", "adoc",
+];
+
+// Helper function to normalize `--ext` values: lowercase, without a leading dot
+fn normalize_extensions(extensions: Vec<String>) -> Vec<String> {
+    extensions
+        .into_iter()
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+// Helper function to check if a file has one of the given (normalized) extensions
+fn is_text_file(path: &std::path::Path, extensions: &[String]) -> bool {
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| TEXT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .map(|ext| extensions.iter().any(|allowed| allowed == &ext.to_lowercase()))
         .unwrap_or(false)
 }
 
+/// Parse a file size such as `5MB`, `500k` or `1048576` (binary units: 1 KB = 1024 bytes)
+pub fn parse_file_size(value: &str) -> std::result::Result<u64, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        _ => return Err(format!("unknown size unit '{}' (use B, KB, MB or GB)", unit.trim())),
+    };
+    let number: u64 = number.parse().map_err(|_| format!("invalid size '{}'", value))?;
+    number.checked_mul(multiplier).ok_or_else(|| format!("size '{}' is too large", value))
+}
+
+// Helper function to format a byte count for messages, e.g. "12.3 MB"
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// retain-files asks before uploading more than this many files or bytes
+const CONFIRM_IMPORT_FILES: usize = 100;
+const CONFIRM_IMPORT_BYTES: u64 = 10 << 20;
+
+// Helper function deciding whether an upload is big enough to confirm first
+fn is_large_import(files: usize, bytes: u64) -> bool {
+    files > CONFIRM_IMPORT_FILES || bytes > CONFIRM_IMPORT_BYTES
+}
+
+// Helper function for --context-from-path: the name of the directory holding a file
+fn parent_dir_name(path: &Path) -> Option<String> {
+    path.parent()?.file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+// Helper function to tell a glob pattern such as "notes/**/*.md" from a plain path
+fn has_glob_metachars(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+// Helper function to find the directory a glob pattern searches from: its leading
+// components without metacharacters. Document IDs and the manifest are relative to it.
+fn glob_root(pattern: &Path) -> PathBuf {
+    pattern
+        .components()
+        .take_while(|component| !has_glob_metachars(Path::new(component.as_os_str())))
+        .collect()
+}
+
+// Helper function to list the files matching a glob pattern, in sorted order
+fn expand_glob(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.to_string_lossy();
+    let mut files = Vec::new();
+    for entry in glob::glob(&pattern).with_context(|| format!("Invalid glob pattern {}", pattern))? {
+        let path = entry?;
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("No files match {}", pattern);
+    }
+    Ok(files)
+}
+
+/// Result of reading a candidate file for `retain-files`
+#[derive(Debug, PartialEq)]
+enum FileContent {
+    Text(String),
+    /// Not imported, with the reason shown to the user
+    Skipped(String),
+}
+
+// Helper function to read a file as UTF-8 text, skipping files over `max_size` without
+// reading them and files that aren't valid UTF-8 (usually binary)
+fn read_text_file(path: &Path, max_size: u64) -> Result<FileContent> {
+    let size = fs::metadata(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?
+        .len();
+    if size > max_size {
+        return Ok(FileContent::Skipped(format!(
+            "{} exceeds --max-file-size ({})",
+            format_file_size(size),
+            format_file_size(max_size)
+        )));
+    }
+
+    let bytes = fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(FileContent::Text(text)),
+        Err(e) => Ok(FileContent::Skipped(format!(
+            "not valid UTF-8 text (invalid byte at offset {}; binary file?)",
+            e.utf8_error().valid_up_to()
+        ))),
+    }
+}
+
+/// Metadata read from a leading `---` YAML front matter block in a Markdown file
+/// (and written by `export --out-dir`)
+#[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+}
+
+// Helper function to check if a file is Markdown (the only format with front matter)
+fn is_markdown_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+        .unwrap_or(false)
+}
+
+// Helper function to split a leading YAML front matter block from the body.
+// Content without a well-formed block (missing closing delimiter, invalid YAML)
+// is returned unchanged so it's imported exactly as before.
+fn split_front_matter(content: &str) -> (FrontMatter, &str) {
+    let rest = match content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    {
+        Some(rest) => rest,
+        None => return (FrontMatter::default(), content),
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return match serde_yaml::from_str::<Option<FrontMatter>>(yaml) {
+                Ok(front_matter) => (front_matter.unwrap_or_default(), body),
+                Err(_) => (FrontMatter::default(), content),
+            };
+        }
+        offset += line.len();
+    }
+
+    (FrontMatter::default(), content)
+}
+
+// Helper function to parse a front matter or import date (RFC 3339 or YYYY-MM-DD)
+fn parse_date_value(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let date = date.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(date) {
+        return Some(dt.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Machine-readable outcome of `retain`: the server's response plus the document ID,
+/// which the CLI may have generated
+#[derive(Debug, serde::Serialize)]
+struct RetainOutput<'a> {
+    document_id: &'a str,
+    #[serde(flatten)]
+    result: &'a MemoryPutResult,
+}
+
+/// Machine-readable outcome of `retain-files`, printed for json/yaml output
+#[derive(Debug, Default, serde::Serialize)]
+struct ImportSummary {
+    files_found: usize,
+    files_read: usize,
+    skipped_unchanged: usize,
+    /// Files left out because they were too large or not text, with the reason
+    skipped_files: Vec<String>,
+    stored_count: i64,
+    batches: usize,
+    is_async: bool,
+    /// Ctrl-C stopped the upload; batches after the ones already sent were skipped
+    interrupted: bool,
+    operation_ids: Vec<String>,
+    failures: Vec<String>,
+    /// Files in batches that were rejected or whose operation failed
+    failed_files: Vec<String>,
+}
+
+/// One record in an import file. Accepts both the hand-written shape
+/// (`content`, `timestamp`) and the shape written by `memory export` (`text`, `date`).
+#[derive(Debug, serde::Deserialize)]
+struct ImportRecord {
+    #[serde(alias = "text")]
+    content: String,
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default, alias = "date")]
+    timestamp: Option<String>,
+    #[serde(default)]
+    document_id: Option<String>,
+}
+
+// Helper function to parse an import file into memory items. Shape errors
+// carry serde's line/column, record-level problems carry the record index.
+fn parse_import_records(json: &str) -> Result<Vec<MemoryItem>> {
+    let records: Vec<ImportRecord> = serde_json::from_str(json)
+        .context("Expected a JSON array of objects with a \"content\" field")?;
+
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            if record.content.trim().is_empty() {
+                anyhow::bail!("Record {}: field \"content\" is empty", i);
+            }
+
+            let timestamp = match record.timestamp.as_deref().map(str::trim) {
+                None | Some("") => None,
+                Some(date) => Some(parse_date_value(date).with_context(|| {
+                    format!("Record {}: field \"timestamp\" is not a valid date: {}", i, date)
+                })?),
+            };
+
+            Ok(MemoryItem {
+                content: record.content,
+                context: record.context.filter(|c| !c.trim().is_empty()),
+                metadata: None,
+                timestamp,
+                document_id: record.document_id,
+                entities: None,
+                tags: None,
+            })
+        })
+        .collect()
+}
+
+// Helper function to derive one document ID per file. The file stem is used so
+// re-imports keep stable IDs; files sharing a stem fall back to their path relative
+// to the import root so they don't collapse into a single document.
+fn document_ids_for(files: &[PathBuf], root: &Path) -> Vec<String> {
+    let stem_of = |file: &PathBuf| {
+        file.file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string())
+    };
+
+    let mut stem_counts: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        if let Some(stem) = stem_of(file) {
+            *stem_counts.entry(stem).or_insert(0) += 1;
+        }
+    }
+
+    files
+        .iter()
+        .map(|file| match stem_of(file) {
+            Some(stem) if stem_counts[&stem] == 1 => stem,
+            Some(_) => {
+                let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            }
+            None => config::generate_doc_id(),
+        })
+        .collect()
+}
+
+// Helper function to resolve a `-` argument to the contents of the given reader (stdin)
+fn read_content_arg<R: Read>(value: String, mut reader: R) -> Result<String> {
+    if value != "-" {
+        return Ok(value);
+    }
+
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .context("Failed to read content from stdin")?;
+
+    if content.trim().is_empty() {
+        anyhow::bail!("No content received on stdin");
+    }
+
+    Ok(content)
+}
+
+// Helper function to read `retain --file` as a single memory
+fn read_content_file(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    if content.trim().is_empty() {
+        anyhow::bail!("File is empty: {}", path.display());
+    }
+    Ok(content)
+}
+
+// Helper function for `retain --from-clipboard`: the clipboard's text as a single memory
+fn read_clipboard_content() -> Result<String> {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .context("Could not read text from the clipboard")?;
+    clipboard_content(&text)
+}
+
+// Helper function dropping trailing whitespace (a copied line usually ends in a newline);
+// an empty clipboard is refused
+fn clipboard_content(text: &str) -> Result<String> {
+    let content = text.trim_end();
+    if content.trim_start().is_empty() {
+        anyhow::bail!("The clipboard is empty; nothing to retain");
+    }
+    Ok(content.to_string())
+}
+
+// Helper function to resolve a `-` query to a single query read from stdin,
+// dropping the trailing newline that `echo` and friends add
+fn read_query_arg<R: Read>(value: String, reader: R) -> Result<String> {
+    let query = read_content_arg(value, reader)?;
+    Ok(query.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// Helper function for the client-side --context filter: case-insensitive substring
+// match, and facts without a context never match
+fn context_matches(context: Option<&str>, needle: &str) -> bool {
+    context.is_some_and(|context| context.to_lowercase().contains(&needle.to_lowercase()))
+}
+
+/// Fact types accepted by `recall --fact-type`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum FactType {
+    World,
+    Experience,
+    Opinion,
+    /// Shortcut for world, experience and opinion
+    All,
+}
+
+impl FactType {
+    const EVERY: [FactType; 3] = [FactType::World, FactType::Experience, FactType::Opinion];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            FactType::World => "world",
+            FactType::Experience => "experience",
+            FactType::Opinion => "opinion",
+            FactType::All => "all",
+        }
+    }
+}
+
+/// API type names for `--fact-type` values, with `all` expanded and duplicates dropped
+fn expand_fact_types(fact_types: &[FactType]) -> Vec<String> {
+    let mut expanded: Vec<FactType> = Vec::new();
+    for &fact_type in fact_types {
+        let types: &[FactType] = if fact_type == FactType::All { &FactType::EVERY } else { &[fact_type] };
+        for &t in types {
+            if !expanded.contains(&t) {
+                expanded.push(t);
+            }
+        }
+    }
+    expanded.into_iter().map(|t| t.as_str().to_string()).collect()
+}
+
+/// Client-side `--since`/`--until` window over a fact's `occurred_start`. Both bounds are
+/// inclusive; a date-only `--until` covers that whole day.
+#[derive(Debug, Default)]
+struct DateWindow {
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl DateWindow {
+    fn parse(since: Option<&str>, until: Option<&str>) -> Result<Self> {
+        let since = since.map(|value| parse_window_bound("--since", value, false)).transpose()?;
+        let until = until.map(|value| parse_window_bound("--until", value, true)).transpose()?;
+        if let (Some(since), Some(until)) = (since, until) {
+            if since > until {
+                anyhow::bail!("--since must not be later than --until");
+            }
+        }
+        Ok(Self { since, until })
+    }
+
+    fn is_set(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    // Facts without a parseable date never match once a bound is set
+    fn contains(&self, date: Option<&str>) -> bool {
+        match date.and_then(parse_date_value) {
+            Some(date) => self.since.is_none_or(|since| date >= since) && self.until.is_none_or(|until| date <= until),
+            None => false,
+        }
+    }
+}
+
+// Helper function to parse a --since/--until value; date-only values start (or, for
+// --until, end) at midnight UTC
+fn parse_window_bound(flag: &str, value: &str, end_of_day: bool) -> Result<chrono::DateTime<chrono::Utc>> {
+    let value = value.trim();
+    if end_of_day {
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            if let Some(end) = date.and_hms_nano_opt(23, 59, 59, 999_999_999) {
+                return Ok(end.and_utc());
+            }
+        }
+    }
+    parse_date_value(value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid {} date '{}': expected YYYY-MM-DD or RFC 3339 (e.g. 2024-01-31T09:00:00Z)",
+            flag,
+            value
+        )
+    })
+}
+
+// Helper function to split a comma-separated bank argument into bank IDs
+fn parse_bank_ids(value: &str) -> Result<Vec<String>> {
+    let bank_ids: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    if bank_ids.is_empty() {
+        anyhow::bail!("No bank ID given");
+    }
+    Ok(bank_ids)
+}
+
+// Helper function to run the same recall against several banks concurrently,
+// one thread per bank. Responses come back in bank order.
+fn recall_banks(
+    client: &ApiClient,
+    bank_ids: &[String],
+    request: &RecallRequest,
+    verbose: Verbosity,
+) -> Vec<Result<RecallResponse>> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = bank_ids
+            .iter()
+            .map(|bank_id| scope.spawn(move || client.recall(bank_id, request, verbose)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("recall worker panicked"))
+            .collect()
+    })
+}
+
+/// How recall uses the local result cache
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheMode {
+    /// Always ask the server and keep nothing
+    Off,
+    /// Ask the server and save each response (`--cache`)
+    Store,
+    /// Serve saved responses no older than the TTL, never calling the server (`--offline`)
+    Offline(Duration),
+}
+
+// Helper function recalling from every bank, through the local cache for --cache/--offline
+fn fetch_recalls(
+    client: &ApiClient,
+    bank_ids: &[String],
+    request: &RecallRequest,
+    cache_mode: CacheMode,
+    verbose: Verbosity,
+) -> Result<Vec<Result<RecallResponse>>> {
+    if cache_mode == CacheMode::Off {
+        return Ok(recall_banks(client, bank_ids, request, verbose));
+    }
+    let dir = recall_cache::cache_dir().ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?;
+    let key = |bank_id: &str| recall_cache::cache_key(client.base_url(), bank_id, request);
+
+    if let CacheMode::Offline(ttl) = cache_mode {
+        return Ok(bank_ids
+            .iter()
+            .map(|bank_id| {
+                recall_cache::load(&dir, &key(bank_id), ttl)?.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No cached results for bank {} and this query (run it with --cache while online first)",
+                        bank_id
+                    )
+                })
+            })
+            .collect());
+    }
+
+    let responses = recall_banks(client, bank_ids, request, verbose);
+    for (bank_id, response) in bank_ids.iter().zip(&responses) {
+        if let Ok(response) = response {
+            // The results are still shown if they can't be saved
+            if let Err(e) = recall_cache::store(&dir, &key(bank_id), response) {
+                eprintln!("warning: Could not cache results: {:#}", e);
+            }
+        }
+    }
+    Ok(responses)
+}
+
+// Helper function for --queries-file: one query per non-blank line
+fn parse_queries(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Helper function applying the client-side filters: --context, --since/--until and --dedup
+fn filter_results<T: AsRecallResult>(
+    results: &mut Vec<T>,
+    context_filter: Option<&str>,
+    window: &DateWindow,
+    dedup: bool,
+    verbose: Verbosity,
+) {
+    if let Some(needle) = context_filter {
+        results.retain(|r| context_matches(r.recall_result().context.as_deref(), needle));
+    }
+    if window.is_set() {
+        results.retain(|r| window.contains(r.recall_result().occurred_start.as_deref()));
+    }
+    if dedup {
+        report_duplicates(sort::dedup_results(results), verbose);
+    }
+}
+
+// Helper function for --dedup's verbose note
+fn report_duplicates(removed: usize, verbose: Verbosity) {
+    if verbose.is_verbose() && removed > 0 {
+        eprintln!("Collapsed {} duplicate result(s)", removed);
+    }
+}
+
 pub fn recall(
     client: &ApiClient,
     agent_id: &str,
     query: String,
-    fact_type: Vec<String>,
+    fact_type: Vec<FactType>,
     budget: String,
     max_tokens: i64,
     trace: bool,
     include_chunks: bool,
     chunk_max_tokens: i64,
-    verbose: bool,
+    limit: Option<usize>,
+    sort_by: SortBy,
+    stable_order: bool,
+    context_filter: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    highlight: bool,
+    group_by_type: bool,
+    summary_only: bool,
+    dedup: bool,
+    keep_going: bool,
+    copy: bool,
+    fail_on_empty: bool,
+    cache_mode: CacheMode,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
+    if limit == Some(0) {
+        anyhow::bail!("--limit must be at least 1");
+    }
+    if stable_order && sort_by != SortBy::Date {
+        anyhow::bail!("--stable-order needs --sort date: the server's ranking has no score to find ties on");
+    }
+    let window = DateWindow::parse(since.as_deref(), until.as_deref())?;
+    let query = read_query_arg(query, io::stdin().lock())?;
+    let bank_ids = parse_bank_ids(agent_id)?;
+    let highlight_terms = if highlight && output_format == OutputFormat::Pretty {
+        ui::query_terms(&query)
+    } else {
+        Vec::new()
+    };
+
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Recalling memories..."))
     } else {
@@ -65,7 +667,7 @@ pub fn recall(
 
     let request = RecallRequest {
         query,
-        types: if fact_type.is_empty() { None } else { Some(fact_type) },
+        types: if fact_type.is_empty() { None } else { Some(expand_fact_types(&fact_type)) },
         budget: Some(parse_budget(&budget)),
         max_tokens,
         trace,
@@ -75,25 +677,317 @@ pub fn recall(
         tags_match: TagsMatch::Any,
     };
 
-    let response = client.recall(agent_id, &request, verbose);
+    if bank_ids.len() > 1 {
+        let responses = fetch_recalls(client, &bank_ids, &request, cache_mode, verbose)?;
+
+        if let Some(mut sp) = spinner {
+            sp.finish();
+        }
+
+        let mut results = Vec::new();
+        let mut failures = Vec::new();
+        for (bank_id, response) in bank_ids.iter().zip(responses) {
+            let response = match response {
+                Ok(response) => response,
+                Err(e) if keep_going => {
+                    failures.push(format!("Recall failed for bank {}: {:#}", bank_id, e));
+                    continue;
+                }
+                Err(e) => return Err(e.context(format!("Recall failed for bank {}", bank_id))),
+            };
+            results.extend(response.results.into_iter().map(|result| BankRecallResult {
+                bank_id: bank_id.clone(),
+                result,
+            }));
+        }
+
+        filter_results(&mut results, context_filter.as_deref(), &window, dedup, verbose);
+        let total = results.len();
+        sort::sort_results(&mut results, sort_by, stable_order);
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+
+        let response = MultiBankRecallResponse { results, failures: failures.clone() };
+        if summary_only {
+            let mut counts = recall_counts(&response.results);
+            counts.banks = bank_ids.iter().map(|bank_id| (bank_id.clone(), 0)).collect();
+            for result in &response.results {
+                *counts.banks.entry(result.bank_id.clone()).or_default() += 1;
+            }
+            print_recall_counts(&counts, output_format)?;
+        } else if output_format == OutputFormat::Pretty {
+            pager::start();
+            ui::print_multi_bank_results(&response.results, total, &highlight_terms, group_by_type);
+        } else {
+            output::print_output(&response, output_format)?;
+        }
+        if copy {
+            copy_to_clipboard(&output::to_json(&response)?, "results as JSON", output_format);
+        }
+        history::record("recall", agent_id, &request.query, Some(response.results.len()));
+
+        if !failures.is_empty() {
+            for failure in &failures {
+                ui::print_error(failure);
+            }
+            anyhow::bail!("Recall failed for {} of {} banks", failures.len(), bank_ids.len());
+        }
+        if fail_on_empty && response.results.is_empty() {
+            return Err(NoResultsError.into());
+        }
+        return Ok(());
+    }
+
+    let response = fetch_recalls(client, &bank_ids, &request, cache_mode, verbose)?
+        .pop()
+        .expect("one response per bank");
 
     if let Some(mut sp) = spinner {
         sp.finish();
     }
 
     match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_search_results(&result, trace, include_chunks);
+        Ok(mut result) => {
+            filter_results(&mut result.results, context_filter.as_deref(), &window, dedup, verbose);
+            let total = result.results.len();
+            sort::sort_results(&mut result.results, sort_by, stable_order);
+            if let Some(limit) = limit {
+                result.results.truncate(limit);
+            }
+
+            if summary_only {
+                print_recall_counts(&recall_counts(&result.results), output_format)?;
+            } else if output_format == OutputFormat::Pretty {
+                pager::start();
+                ui::print_search_results(&result, total, trace, include_chunks, &highlight_terms, group_by_type);
             } else {
                 output::print_output(&result, output_format)?;
             }
+            if copy {
+                copy_to_clipboard(&output::to_json(&result)?, "results as JSON", output_format);
+            }
+            history::record("recall", agent_id, &request.query, Some(result.results.len()));
+            if fail_on_empty && result.results.is_empty() {
+                return Err(NoResultsError.into());
+            }
             Ok(())
         }
         Err(e) => Err(e)
     }
 }
 
+/// Machine-readable outcome of `recall --summary-only`
+#[derive(Debug, Default, PartialEq, serde::Serialize)]
+struct RecallCounts {
+    /// Results per fact type
+    counts: BTreeMap<String, usize>,
+    total: usize,
+    /// Results per bank, for a multi-bank recall
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    banks: BTreeMap<String, usize>,
+}
+
+// Helper function for --summary-only: tally the results that would have been shown
+// by fact type, counting those without one as "unknown"
+fn recall_counts<T: AsRecallResult>(results: &[T]) -> RecallCounts {
+    let mut counts = RecallCounts { total: results.len(), ..RecallCounts::default() };
+    for result in results {
+        let fact_type = result.recall_result().type_.as_deref().unwrap_or("unknown");
+        *counts.counts.entry(fact_type.to_string()).or_default() += 1;
+    }
+    counts
+}
+
+// Helper function to print a --summary-only tally instead of the results
+fn print_recall_counts(counts: &RecallCounts, output_format: OutputFormat) -> Result<()> {
+    if output_format != OutputFormat::Pretty {
+        return output::print_output(counts, output_format);
+    }
+    ui::print_info(&format!("{} results", counts.total));
+    for (fact_type, count) in &counts.counts {
+        println!("  {}: {}", fact_type, count);
+    }
+    if !counts.banks.is_empty() {
+        println!("  By bank:");
+        for (bank_id, count) in &counts.banks {
+            println!("    {}: {}", bank_id, count);
+        }
+    }
+    Ok(())
+}
+
+// Helper function listing the placeholders --template can use
+fn template_vars(text: &str, bank: &str, query: &str, mental_model_count: usize) -> Vec<(&'static str, String)> {
+    vec![
+        ("text", text.to_string()),
+        ("bank", bank.to_string()),
+        ("query", query.to_string()),
+        ("mental_model_count", mental_model_count.to_string()),
+    ]
+}
+
+// Helper function to fill in a --template: `{name}` is replaced by its value and
+// `{{`/`}}` print literal braces. Unknown or unclosed placeholders are errors.
+fn render_template(template: &str, vars: &[(&str, String)]) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => anyhow::bail!("Unclosed placeholder {{{} in --template", name),
+                    }
+                }
+                let value = vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value);
+                match value {
+                    Some(value) => rendered.push_str(value),
+                    None => {
+                        let available: Vec<String> = vars.iter().map(|(var, _)| format!("{{{}}}", var)).collect();
+                        anyhow::bail!("Unknown placeholder {{{}}} in --template (available: {})", name, available.join(", "));
+                    }
+                }
+            }
+            '}' => anyhow::bail!("Unmatched '}}' in --template (use '}}}}' for a literal brace)"),
+            c => rendered.push(c),
+        }
+    }
+    Ok(rendered)
+}
+
+/// `recall --queries-file`: run every query in a file against one bank, a few at a
+/// time, for evaluation runs. Results are grouped per query; a failed query is
+/// reported alongside the others and makes the command exit non-zero at the end.
+pub fn recall_queries_file(
+    client: &ApiClient,
+    agent_id: &str,
+    query: Option<String>,
+    path: &Path,
+    fact_type: Vec<FactType>,
+    budget: String,
+    max_tokens: i64,
+    limit: Option<usize>,
+    sort_by: SortBy,
+    stable_order: bool,
+    context_filter: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    highlight: bool,
+    dedup: bool,
+    concurrency: usize,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if query.is_some() {
+        anyhow::bail!("Give either a query or --queries-file, not both");
+    }
+    if limit == Some(0) {
+        anyhow::bail!("--limit must be at least 1");
+    }
+    if stable_order && sort_by != SortBy::Date {
+        anyhow::bail!("--stable-order needs --sort date: the server's ranking has no score to find ties on");
+    }
+    if concurrency == 0 {
+        anyhow::bail!("--concurrency must be at least 1");
+    }
+    if agent_id.contains(',') {
+        anyhow::bail!("--queries-file runs against a single bank");
+    }
+    let window = DateWindow::parse(since.as_deref(), until.as_deref())?;
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read queries file {}", path.display()))?;
+    let queries = parse_queries(&content);
+    if queries.is_empty() {
+        anyhow::bail!("No queries in {}", path.display());
+    }
+
+    let requests: Vec<RecallRequest> = queries
+        .iter()
+        .map(|query| RecallRequest {
+            query: query.clone(),
+            types: if fact_type.is_empty() { None } else { Some(expand_fact_types(&fact_type)) },
+            budget: Some(parse_budget(&budget)),
+            max_tokens,
+            trace: false,
+            query_timestamp: None,
+            include: None,
+            tags: None,
+            tags_match: TagsMatch::Any,
+        })
+        .collect();
+
+    let pb = ui::create_progress_bar(requests.len() as u64, "Running queries");
+    let next = AtomicUsize::new(0);
+    let mut responses: Vec<Option<Result<RecallResponse>>> = requests.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.min(requests.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(request) = requests.get(i) else {
+                            break;
+                        };
+                        done.push((i, client.recall(agent_id, request, verbose)));
+                        pb.inc(1);
+                    }
+                    done
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, response) in worker.join().expect("recall worker panicked") {
+                responses[i] = Some(response);
+            }
+        }
+    });
+    pb.finish_with_message("Queries done");
+
+    let mut failed = 0;
+    let batches: Vec<QueryRecallResult> = queries
+        .into_iter()
+        .zip(responses)
+        .map(|(query, response)| match response.expect("every query is run") {
+            Ok(mut response) => {
+                filter_results(&mut response.results, context_filter.as_deref(), &window, dedup, verbose);
+                sort::sort_results(&mut response.results, sort_by, stable_order);
+                if let Some(limit) = limit {
+                    response.results.truncate(limit);
+                }
+                QueryRecallResult { query, results: response.results, error: None }
+            }
+            Err(e) => {
+                failed += 1;
+                QueryRecallResult { query, results: Vec::new(), error: Some(format!("{:#}", e)) }
+            }
+        })
+        .collect();
+
+    if output_format == OutputFormat::Pretty {
+        pager::start();
+        ui::print_query_results(&batches, highlight);
+    } else {
+        output::print_output(&batches, output_format)?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} queries failed", failed, batches.len());
+    }
+    Ok(())
+}
+
 pub fn reflect(
     client: &ApiClient,
     agent_id: &str,
@@ -102,9 +996,24 @@ pub fn reflect(
     context: Option<String>,
     max_tokens: Option<i64>,
     schema_path: Option<PathBuf>,
-    verbose: bool,
+    markdown: Option<bool>,
+    template: Option<String>,
+    explain: bool,
+    history_file: Option<PathBuf>,
+    history_tokens: usize,
+    copy: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
+    if let Some(template) = &template {
+        if output_format != OutputFormat::Pretty {
+            anyhow::bail!("--template replaces the pretty output, so it can't be combined with -o");
+        }
+        // Catch unknown placeholders before spending a reflect call
+        render_template(template, &template_vars("", "", "", 0))?;
+    }
+    let query = read_query_arg(query, io::stdin().lock())?;
+
     let spinner = if output_format == OutputFormat::Pretty {
         Some(ui::create_spinner("Reflecting..."))
     } else {
@@ -122,12 +1031,27 @@ pub fn reflect(
         None
     };
 
+    // Earlier turns go ahead of any --context, which is about this query
+    let earlier = match &history_file {
+        Some(path) => conversation::render_context(&conversation::load(path)?, history_tokens),
+        None => None,
+    };
+    let context = match (earlier, context) {
+        (Some(earlier), Some(context)) => Some(format!("{}\n\n{}", earlier, context)),
+        (earlier, context) => earlier.or(context),
+    };
+
     let request = ReflectRequest {
         query,
         budget: Some(parse_budget(&budget)),
         context,
         max_tokens: max_tokens.unwrap_or(4096),
-        include: None,
+        // The server only reports the facts behind an answer when asked to
+        include: if explain {
+            Some(serde_json::from_value(serde_json::json!({ "facts": {} })).context("Failed to build reflect include options")?)
+        } else {
+            None
+        },
         response_schema,
         tags: None,
         tags_match: TagsMatch::Any,
@@ -141,27 +1065,150 @@ pub fn reflect(
 
     match response {
         Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                ui::print_think_response(&result);
+            if let Some(template) = &template {
+                let vars = template_vars(&result.text, agent_id, &request.query, result.mental_models_created.len());
+                println!("{}", render_template(template, &vars)?);
+            } else if output_format == OutputFormat::Pretty {
+                let markdown = markdown.unwrap_or_else(|| io::stdout().is_terminal());
+                pager::start();
+                ui::print_think_response(&result, markdown, explain);
             } else {
                 output::print_output(&result, output_format)?;
             }
+            if copy {
+                copy_to_clipboard(&result.text, "answer", output_format);
+            }
+            history::record("reflect", agent_id, &request.query, None);
+            if let Some(path) = &history_file {
+                let turn = conversation::Turn { query: request.query.clone(), answer: result.text.clone() };
+                conversation::append(path, &turn)?;
+            }
             Ok(())
         }
         Err(e) => Err(e)
     }
 }
 
+// Helper function for --copy. The output has already been printed, so a missing
+// clipboard (e.g. over SSH) is a warning rather than a failed command.
+fn copy_to_clipboard(text: &str, what: &str, output_format: OutputFormat) {
+    let copied = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+    match (copied, output_format) {
+        (Ok(()), OutputFormat::Pretty) => ui::print_success(&format!("Copied {} to clipboard", what)),
+        (Ok(()), _) => {}
+        (Err(e), OutputFormat::Pretty) => ui::print_warning(&format!("Could not copy to clipboard: {}", e)),
+        // Keep stdout clean for structured output
+        (Err(e), _) => eprintln!("warning: Could not copy to clipboard: {}", e),
+    }
+}
+
+// Helper function to pair each content with its --context: none, one shared by
+// every content, or exactly one per content
+fn pair_contexts(content_count: usize, contexts: Vec<String>) -> Result<Vec<Option<String>>> {
+    match contexts.len() {
+        0 => Ok(vec![None; content_count]),
+        1 => Ok(vec![contexts.into_iter().next(); content_count]),
+        n if n == content_count => Ok(contexts.into_iter().map(Some).collect()),
+        n => anyhow::bail!(
+            "Got {} --context values for {} contents; give one shared context or one per content",
+            n,
+            content_count
+        ),
+    }
+}
+
+// Helper function for --chunk-size: split `content` into pieces of at most `max_chars`
+// characters. Each piece ends at the last paragraph break that fits, else the last
+// sentence or line end, else the last space, and only then mid-word.
+fn chunk_content(content: &str, max_chars: usize) -> Vec<String> {
+    const BOUNDARIES: [&[&str]; 3] = [&["\n\n"], &[". ", "! ", "? ", "\n"], &[" "]];
+
+    let mut chunks = Vec::new();
+    let mut rest = content.trim();
+    while rest.chars().count() > max_chars {
+        // Byte offset just past the first `max_chars` characters
+        let limit = rest.char_indices().nth(max_chars).map_or(rest.len(), |(i, _)| i);
+        let window = &rest[..limit];
+        let split = BOUNDARIES
+            .iter()
+            .find_map(|separators| {
+                separators
+                    .iter()
+                    .filter_map(|sep| window.rfind(sep).filter(|&i| i > 0).map(|i| i + sep.len()))
+                    .max()
+            })
+            .unwrap_or(limit);
+        let (chunk, tail) = rest.split_at(split);
+        chunks.push(chunk.trim_end().to_string());
+        rest = tail.trim_start();
+    }
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+// Helper function to split an item longer than --chunk-size into several items of the
+// same document, each with "part N of M" added to its context
+fn chunk_item(item: MemoryItem, chunk_size: Option<usize>) -> Vec<MemoryItem> {
+    let Some(max_chars) = chunk_size else {
+        return vec![item];
+    };
+    let chunks = chunk_content(&item.content, max_chars);
+    if chunks.len() <= 1 {
+        return vec![item];
+    }
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, content)| {
+            let part = format!("part {} of {}", i + 1, total);
+            MemoryItem {
+                content,
+                context: Some(match &item.context {
+                    Some(context) => format!("{} ({})", context, part),
+                    None => part,
+                }),
+                ..item.clone()
+            }
+        })
+        .collect()
+}
+
 pub fn retain(
     client: &ApiClient,
     agent_id: &str,
-    content: String,
+    contents: Vec<String>,
+    file: Option<PathBuf>,
+    from_clipboard: bool,
     doc_id: Option<String>,
-    context: Option<String>,
+    contexts: Vec<String>,
+    chunk_size: Option<usize>,
     r#async: bool,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
+    if chunk_size == Some(0) {
+        anyhow::bail!("--chunk-size must be at least 1");
+    }
+    if contents.iter().filter(|c| c.as_str() == "-").count() > 1 {
+        anyhow::bail!("stdin (\"-\") can only be used for one content value");
+    }
+    let contents = match file {
+        Some(_) if !contents.is_empty() => anyhow::bail!("Give the memory content either inline or with --file, not both"),
+        Some(path) => vec![read_content_file(&path)?],
+        None if from_clipboard && !contents.is_empty() => {
+            anyhow::bail!("Give the memory content either inline or with --from-clipboard, not both")
+        }
+        None if from_clipboard => vec![read_clipboard_content()?],
+        None => contents,
+    };
+    let contexts = pair_contexts(contents.len(), contexts)?;
+    let contents = contents
+        .into_iter()
+        .map(|content| read_content_arg(content, io::stdin().lock()))
+        .collect::<Result<Vec<_>>>()?;
     let doc_id = doc_id.unwrap_or_else(config::generate_doc_id);
 
     let spinner = if output_format == OutputFormat::Pretty {
@@ -170,18 +1217,25 @@ pub fn retain(
         None
     };
 
-    let item = MemoryItem {
-        content: content.clone(),
-        context,
-        metadata: None,
-        timestamp: None,
-        document_id: Some(doc_id.clone()),
-        entities: None,
-        tags: None,
-    };
+    // All items share the document, so they are grouped together server-side
+    let items: Vec<MemoryItem> = contents
+        .into_iter()
+        .zip(contexts)
+        .map(|(content, context)| MemoryItem {
+            content,
+            context,
+            metadata: None,
+            timestamp: None,
+            document_id: Some(doc_id.clone()),
+            entities: None,
+            tags: None,
+        })
+        .flat_map(|item| chunk_item(item, chunk_size))
+        .collect();
+    let item_count = items.len();
 
     let request = RetainRequest {
-        items: vec![item],
+        items,
         async_: r#async,
         document_tags: None,
     };
@@ -195,10 +1249,17 @@ pub fn retain(
     match response {
         Ok(result) => {
             if output_format == OutputFormat::Pretty {
-                ui::print_success(&format!(
-                    "Memory retained successfully (document: {})",
-                    doc_id
-                ));
+                if item_count == 1 {
+                    ui::print_success(&format!(
+                        "Memory retained successfully (document: {})",
+                        doc_id
+                    ));
+                } else {
+                    ui::print_success(&format!(
+                        "{} memories retained successfully (document: {})",
+                        item_count, doc_id
+                    ));
+                }
                 if result.is_async {
                     println!("  Status: queued for background processing");
                     println!("  Items: {}", result.items_count);
@@ -206,7 +1267,7 @@ pub fn retain(
                     println!("  Stored count: {}", result.items_count);
                 }
             } else {
-                output::print_output(&result, output_format)?;
+                output::print_output(&RetainOutput { document_id: &doc_id, result: &result }, output_format)?;
             }
             Ok(())
         }
@@ -214,23 +1275,98 @@ pub fn retain(
     }
 }
 
+/// Which bank `retain-files` stores each file in
+pub enum FileBanks {
+    /// Every file goes to this bank
+    Fixed(String),
+    /// `--bank-from-path`: the bank is the name of the directory this many levels
+    /// below the import root (1 = the top-level folders)
+    FromPath(usize),
+}
+
+// Flags of `retain-files` that apply the same way to every bank it imports into
+struct FileImportSettings {
+    context: Option<String>,
+    context_from_path: bool,
+    batch_size: usize,
+    concurrency: usize,
+    chunk_size: Option<usize>,
+    r#async: bool,
+    force: bool,
+    max_file_size: u64,
+    keep_going: bool,
+    max_consecutive_failures: usize,
+    yes: bool,
+}
+
+// What happened to one bank's files; the counts behind the summary and the exit status
+struct BankImport {
+    summary: ImportSummary,
+    failed_batches: usize,
+    not_submitted: usize,
+    /// The confirmation prompt was declined, so nothing was read or sent
+    cancelled: bool,
+}
+
 pub fn retain_files(
     client: &ApiClient,
-    agent_id: &str,
+    banks: FileBanks,
     path: PathBuf,
     recursive: bool,
+    extensions: Vec<String>,
     context: Option<String>,
+    context_from_path: bool,
+    batch_size: usize,
+    concurrency: usize,
+    chunk_size: Option<usize>,
     r#async: bool,
-    verbose: bool,
+    force: bool,
+    max_file_size: u64,
+    keep_going: bool,
+    max_consecutive_failures: usize,
+    yes: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
-    if !path.exists() {
+    let is_glob = has_glob_metachars(&path);
+    if !is_glob && !path.exists() {
         anyhow::bail!("Path does not exist: {}", path.display());
     }
+    if batch_size == 0 {
+        anyhow::bail!("--batch-size must be at least 1");
+    }
+    if concurrency == 0 {
+        anyhow::bail!("--concurrency must be at least 1");
+    }
+    if chunk_size == Some(0) {
+        anyhow::bail!("--chunk-size must be at least 1");
+    }
+    if max_consecutive_failures == 0 {
+        anyhow::bail!("--max-consecutive-failures must be at least 1");
+    }
+
+    let extensions = normalize_extensions(extensions);
+    if extensions.is_empty() {
+        anyhow::bail!("--ext must list at least one file extension");
+    }
+    if verbose.is_verbose() {
+        eprintln!("Importing files with extensions: {}", extensions.join(", "));
+    }
 
     let mut files = Vec::new();
 
-    if path.is_file() {
+    let root = if is_glob {
+        glob_root(&path)
+    } else if path.is_file() {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    } else {
+        path.clone()
+    };
+
+    if is_glob {
+        // The pattern picks the files, so --ext and --recursive don't apply
+        files = expand_glob(&path)?;
+    } else if path.is_file() {
         files.push(path);
     } else if path.is_dir() {
         if recursive {
@@ -240,7 +1376,7 @@ pub fn retain_files(
                 .filter(|e| e.file_type().is_file())
             {
                 let path = entry.path();
-                if is_text_file(&path) {
+                if is_text_file(path, &extensions) {
                     files.push(path.to_path_buf());
                 }
             }
@@ -248,63 +1384,671 @@ pub fn retain_files(
             for entry in fs::read_dir(&path)? {
                 let entry = entry?;
                 let path = entry.path();
-                if path.is_file() && is_text_file(&path) {
+                if path.is_file() && is_text_file(&path, &extensions) {
                     files.push(path);
                 }
             }
         }
     }
 
+    // The manifest is bookkeeping, not a note, even when .json files are included
+    files.retain(|file| file.file_name().is_none_or(|name| name != manifest::MANIFEST_FILE));
+
     if files.is_empty() {
-        ui::print_warning("No text files found (supported: txt, md, json, yaml, yml, toml, xml, csv, log, rst, adoc)");
+        if output_format == OutputFormat::Pretty {
+            ui::print_warning(&format!("No text files found (supported: {})", extensions.join(", ")));
+        } else {
+            output::print_output(&ImportSummary::default(), output_format)?;
+        }
         return Ok(());
     }
 
-    ui::print_info(&format!("Found {} files to import", files.len()));
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("Found {} files to import", files.len()));
+    }
+
+    // With --force the manifest is still rewritten, so start over if it's unreadable
+    let mut manifest = match Manifest::load(&root) {
+        Ok(manifest) => manifest,
+        Err(_) if force => Manifest::at(&root),
+        Err(e) => return Err(e),
+    };
+
+    let settings = FileImportSettings {
+        context,
+        context_from_path,
+        batch_size,
+        concurrency,
+        chunk_size,
+        r#async,
+        force,
+        max_file_size,
+        keep_going,
+        max_consecutive_failures,
+        yes,
+    };
+
+    match banks {
+        FileBanks::Fixed(agent_id) => {
+            let import = retain_into_bank(client, &agent_id, &files, &root, &mut manifest, &settings, verbose, output_format)?;
+            report_bank_import(import, &settings, output_format)
+        }
+        FileBanks::FromPath(depth) => {
+            retain_by_path(client, depth, &files, &root, &mut manifest, &settings, verbose, output_format)
+        }
+    }
+}
+
+// Helper function for --bank-from-path: the directory name `depth` levels below
+// `root` on the way to `file`, or None when the file isn't nested that deep
+fn bank_for_path(file: &Path, root: &Path, depth: usize) -> Option<String> {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    let dirs: Vec<_> = relative.parent()?.components().collect();
+    match dirs.get(depth.checked_sub(1)?)? {
+        std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+/// Machine-readable outcome of `retain-files --bank-from-path`
+#[derive(Debug, serde::Serialize)]
+struct PathImportSummary<'a> {
+    banks: Vec<BankImportSummary<'a>>,
+    /// Files not nested deep enough to name a bank
+    unassigned_files: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BankImportSummary<'a> {
+    bank_id: &'a str,
+    #[serde(flatten)]
+    summary: &'a ImportSummary,
+}
+
+// Helper function for `retain-files --bank-from-path`: import each bank's files in
+// turn, then report per bank
+fn retain_by_path(
+    client: &ApiClient,
+    depth: usize,
+    files: &[PathBuf],
+    root: &Path,
+    manifest: &mut Manifest,
+    settings: &FileImportSettings,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut unassigned_files = Vec::new();
+    for file in files {
+        match bank_for_path(file, root, depth) {
+            Some(bank_id) => groups.entry(bank_id).or_default().push(file.clone()),
+            None => unassigned_files.push(file.display().to_string()),
+        }
+    }
+    if groups.is_empty() {
+        anyhow::bail!("No file is nested {} directories deep under {}, so no bank could be taken from the path", depth, root.display());
+    }
+    if output_format == OutputFormat::Pretty {
+        ui::print_info(&format!("Importing into {} banks: {}", groups.len(), groups.keys().cloned().collect::<Vec<_>>().join(", ")));
+    }
+
+    let mut imports = Vec::new();
+    for (bank_id, files) in groups {
+        if output_format == OutputFormat::Pretty {
+            println!();
+            ui::print_info(&format!("Bank {} ({} files)", bank_id, files.len()));
+        }
+        let import = retain_into_bank(client, &bank_id, &files, root, manifest, settings, verbose, output_format)?;
+        let interrupted = import.summary.interrupted;
+        imports.push((bank_id, import));
+        if interrupted {
+            break;
+        }
+    }
+
+    let interrupted = imports.iter().any(|(_, import)| import.summary.interrupted);
+    let failed_batches: usize = imports.iter().map(|(_, import)| import.failed_batches).sum();
+    let batch_count: usize = imports.iter().map(|(_, import)| import.summary.batches).sum();
+    let not_submitted: usize = imports.iter().map(|(_, import)| import.not_submitted).sum();
+
+    if output_format == OutputFormat::Pretty {
+        println!();
+        ui::print_info("Summary by bank");
+        for (bank_id, import) in &imports {
+            let summary = &import.summary;
+            if import.cancelled {
+                println!("  {}: cancelled", bank_id);
+                continue;
+            }
+            let mut parts = vec![format!("{} files uploaded", summary.files_read), format!("{} items", summary.stored_count)];
+            if summary.skipped_unchanged > 0 {
+                parts.push(format!("{} unchanged", summary.skipped_unchanged));
+            }
+            if !summary.skipped_files.is_empty() {
+                parts.push(format!("{} skipped", summary.skipped_files.len()));
+            }
+            if !summary.failed_files.is_empty() {
+                parts.push(format!("{} failed", summary.failed_files.len()));
+            }
+            println!("  {}: {}", bank_id, parts.join(", "));
+        }
+        for (bank_id, import) in &imports {
+            for skipped in &import.summary.skipped_files {
+                ui::print_warning(&format!("Skipped {} ({})", skipped, bank_id));
+            }
+        }
+        for file in &unassigned_files {
+            ui::print_warning(&format!("Skipped {}: not nested {} directories deep, so it has no bank", file, depth));
+        }
+        if interrupted {
+            ui::print_warning("Upload interrupted; batches already sent are still processed by the server");
+        }
+    } else {
+        let summary = PathImportSummary {
+            banks: imports
+                .iter()
+                .map(|(bank_id, import)| BankImportSummary { bank_id, summary: &import.summary })
+                .collect(),
+            unassigned_files,
+        };
+        output::print_output(&summary, output_format)?;
+    }
+
+    for (bank_id, import) in &imports {
+        if !import.summary.failures.is_empty() {
+            ui::print_error(&format!("Bank {}:", bank_id));
+            print_import_failures(&import.summary);
+        }
+    }
+    if interrupted {
+        return Err(InterruptedError { submitted: batch_count - not_submitted, total: batch_count }.into());
+    }
+    if failed_batches > 0 {
+        anyhow::bail!("{} of {} retain batches failed or were not submitted", failed_batches, batch_count);
+    }
+    Ok(())
+}
+
+// Helper function to list failed batches and the files in them on stderr
+fn print_import_failures(summary: &ImportSummary) {
+    for failure in &summary.failures {
+        ui::print_error(failure);
+    }
+    if !summary.failed_files.is_empty() {
+        ui::print_error(&format!("{} files were not retained:", summary.failed_files.len()));
+        for file in &summary.failed_files {
+            eprintln!("  {}", file);
+        }
+    }
+}
+
+// Helper function to print the outcome of a single-bank `retain-files` and turn
+// failures into the exit status
+fn report_bank_import(import: BankImport, settings: &FileImportSettings, output_format: OutputFormat) -> Result<()> {
+    if import.cancelled {
+        return Ok(());
+    }
+    let summary = import.summary;
+    let interrupted = summary.interrupted;
+    let batch_count = summary.batches;
+
+    if batch_count == 0 {
+        // Nothing was left to send after skipping unchanged and unreadable files
+        if output_format == OutputFormat::Pretty {
+            if summary.skipped_files.is_empty() {
+                ui::print_success(&format!(
+                    "All {} files are unchanged since the last run (use --force to retain them again)",
+                    summary.skipped_unchanged
+                ));
+            } else {
+                for skipped in &summary.skipped_files {
+                    ui::print_warning(&format!("Skipped {}", skipped));
+                }
+                ui::print_warning("No files left to retain");
+            }
+        } else {
+            output::print_output(&summary, output_format)?;
+        }
+        return Ok(());
+    }
+
+    if output_format == OutputFormat::Pretty {
+        if interrupted {
+            ui::print_warning("Upload interrupted; batches already sent are still processed by the server");
+            println!("  Items queued: {}", summary.stored_count);
+            for op_id in &summary.operation_ids {
+                println!("  Operation ID: {}", op_id);
+            }
+        } else if settings.r#async {
+            // User requested async mode - return immediately
+            ui::print_success("Files queued for processing");
+            println!("  Items: {}", summary.stored_count);
+            for op_id in &summary.operation_ids {
+                println!("  Operation ID: {}", op_id);
+            }
+        } else {
+            ui::print_success("Files retained successfully");
+            println!("  Items processed: {}", summary.stored_count);
+        }
+        println!("  Files uploaded: {}", summary.files_read);
+        if summary.skipped_unchanged > 0 {
+            println!("  Files skipped (unchanged): {}", summary.skipped_unchanged);
+        }
+        for skipped in &summary.skipped_files {
+            ui::print_warning(&format!("Skipped {}", skipped));
+        }
+    } else {
+        output::print_output(&summary, output_format)?;
+    }
+
+    print_import_failures(&summary);
+    if !summary.failures.is_empty() && !interrupted {
+        anyhow::bail!("{} of {} retain batches failed or were not submitted", import.failed_batches, batch_count);
+    }
+    if interrupted {
+        return Err(InterruptedError { submitted: batch_count - import.not_submitted, total: batch_count }.into());
+    }
+
+    Ok(())
+}
 
-    let pb = ui::create_progress_bar(files.len() as u64, "Processing files");
+// Helper function doing the work of `retain-files` for one bank: read the files,
+// skip unchanged ones, upload them in batches, wait for processing unless --async,
+// and record what went through in the manifest. Nothing is reported here.
+fn retain_into_bank(
+    client: &ApiClient,
+    agent_id: &str,
+    files: &[PathBuf],
+    root: &Path,
+    manifest: &mut Manifest,
+    settings: &FileImportSettings,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<BankImport> {
+    let FileImportSettings {
+        context,
+        context_from_path,
+        batch_size,
+        concurrency,
+        chunk_size,
+        r#async,
+        force,
+        max_file_size,
+        keep_going,
+        max_consecutive_failures,
+        yes,
+    } = settings;
+    let (batch_size, r#async, keep_going, max_consecutive_failures) = (*batch_size, *r#async, *keep_going, *max_consecutive_failures);
+
+    // Progress goes by bytes so a single large file doesn't look stuck
+    let sizes: Vec<u64> = files.iter().map(|file| fs::metadata(file).map(|m| m.len()).unwrap_or(0)).collect();
+    let pb = ui::create_bytes_progress_bar(sizes.iter().sum(), "Processing files");
 
     let mut items = Vec::new();
+    // Manifest key and content hash for each entry in `items` (repeated for each part
+    // of a file split by --chunk-size)
+    let mut hashes = Vec::new();
+    let mut files_read = 0;
+    let mut skipped_unchanged = 0;
+    let mut skipped_files = Vec::new();
+    let doc_ids = document_ids_for(files, root);
+
+    for (n, ((file_path, doc_id), size)) in files.iter().zip(doc_ids).zip(&sizes).enumerate() {
+        pb.set_message(format!("Processing files ({}/{})", n + 1, files.len()));
+        let content = match read_text_file(file_path, *max_file_size)? {
+            FileContent::Text(content) => content,
+            FileContent::Skipped(reason) => {
+                if verbose.is_verbose() {
+                    eprintln!("Skipping {}: {}", file_path.display(), reason);
+                }
+                skipped_files.push(format!("{}: {}", file_path.display(), reason));
+                pb.inc(*size);
+                continue;
+            }
+        };
+
+        let key = file_path.strip_prefix(root).unwrap_or(file_path).to_string_lossy().into_owned();
+        let hash = manifest::content_hash(content.as_bytes());
+        if !force && manifest.is_unchanged(agent_id, &key, &hash) {
+            if verbose.is_verbose() {
+                eprintln!("Skipping unchanged file {}", file_path.display());
+            }
+            skipped_unchanged += 1;
+            pb.inc(*size);
+            continue;
+        }
 
-    for file_path in &files {
-        let content = fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        // Markdown front matter supplies per-file context and date
+        let (front_matter, body) = if is_markdown_file(file_path) {
+            split_front_matter(&content)
+        } else {
+            (FrontMatter::default(), content.as_str())
+        };
 
-        let doc_id = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(config::generate_doc_id);
+        let timestamp = front_matter.date.as_deref().and_then(|date| {
+            let parsed = parse_date_value(date);
+            if parsed.is_none() && verbose.is_verbose() {
+                eprintln!("Ignoring unparseable date '{}' in {}", date, file_path.display());
+            }
+            parsed
+        });
 
-        items.push(MemoryItem {
-            content,
-            context: context.clone(),
+        let item = MemoryItem {
+            content: body.to_string(),
+            context: front_matter.context.or_else(|| {
+                if *context_from_path {
+                    parent_dir_name(file_path)
+                } else {
+                    context.clone()
+                }
+            }),
             metadata: None,
-            timestamp: None,
+            timestamp,
             document_id: Some(doc_id),
             entities: None,
             tags: None,
-        });
+        };
+        for part in chunk_item(item, *chunk_size) {
+            items.push(part);
+            hashes.push((key.clone(), hash.clone()));
+        }
+        files_read += 1;
+
+        pb.inc(*size);
+    }
+
+    pb.finish_with_message("Files processed");
+
+    let mut summary = ImportSummary {
+        files_found: files.len(),
+        files_read,
+        skipped_unchanged,
+        skipped_files,
+        is_async: r#async,
+        ..ImportSummary::default()
+    };
+    if items.is_empty() {
+        return Ok(BankImport { summary, failed_batches: 0, not_submitted: 0, cancelled: false });
+    }
+
+    // Give a heads-up before big uploads; scripts (--yes, or stdout not a terminal) aren't asked
+    let total_bytes: u64 = items.iter().map(|item| item.content.len() as u64).sum();
+    if !yes && output_format == OutputFormat::Pretty && io::stdout().is_terminal() && is_large_import(files_read, total_bytes) {
+        let mode = if r#async { "queue them for background processing" } else { "wait for processing" };
+        let message = format!(
+            "Retain {} files ({}) into bank {} and {}?",
+            files_read,
+            format_file_size(total_bytes),
+            agent_id,
+            mode
+        );
+        if !ui::prompt_confirmation(&message)? {
+            ui::print_info("Import cancelled");
+            return Ok(BankImport { summary, failed_batches: 0, not_submitted: 0, cancelled: true });
+        }
+    }
+
+    // Always use async mode for the API call; batches are submitted in parallel
+    let requests: Vec<RetainRequest> = items
+        .chunks(batch_size)
+        .map(|batch| RetainRequest {
+            items: batch.to_vec(),
+            async_: true,
+            document_tags: None,
+        })
+        .collect();
+    let batch_count = requests.len();
+
+    // From here on Ctrl-C stops the upload cleanly instead of killing it mid-batch
+    interrupt::install();
+    let upload_pb = ui::create_bytes_progress_bar(requests.iter().map(batch_bytes).sum(), "Uploading batches");
+    let results = submit_batches(client, agent_id, &requests, *concurrency, keep_going, max_consecutive_failures, verbose, &upload_pb);
+    let interrupted = interrupt::is_interrupted();
+    if interrupted {
+        upload_pb.finish_and_clear();
+    } else {
+        upload_pb.finish_with_message("Batches uploaded");
+    }
+
+    let mut items_count = 0;
+    let mut operation_ids = Vec::new();
+    let mut failures = Vec::new();
+    // Indexes of batches that were rejected or whose operation failed
+    let mut failed_batches = BTreeSet::new();
+    let mut operation_batches = HashMap::new();
+    let mut not_submitted = 0;
+
+    for (i, result) in results.into_iter().enumerate() {
+        let Some(result) = result else {
+            not_submitted += 1;
+            failed_batches.insert(i);
+            continue;
+        };
+        match result {
+            Ok(result) => {
+                items_count += result.items_count;
+                if let Some(operation_id) = result.operation_id {
+                    operation_batches.insert(operation_id.clone(), i);
+                    operation_ids.push(operation_id);
+                }
+            }
+            Err(e) => {
+                failures.push(format!("Batch {} of {}: {:#}", i + 1, batch_count, e));
+                failed_batches.insert(i);
+            }
+        }
+    }
+    if not_submitted > 0 && interrupted {
+        failures.push(format!("{} of {} batches were not submitted because of Ctrl-C", not_submitted, batch_count));
+    } else if not_submitted > 0 && keep_going {
+        failures.push(format!(
+            "{} of {} batches were not submitted after {} batches failed in a row; check the server's health and re-run (retained files are skipped)",
+            not_submitted, batch_count, max_consecutive_failures
+        ));
+    } else if not_submitted > 0 {
+        failures.push(format!(
+            "{} of {} batches were not submitted after the first failure (use --keep-going to submit them anyway)",
+            not_submitted, batch_count
+        ));
+    }
+
+    // After Ctrl-C the batches already accepted are left to the server, as in async mode
+    if !r#async && !interrupted && !operation_ids.is_empty() {
+        // Poll until every submitted batch completes
+        let poll_spinner = if output_format == OutputFormat::Pretty {
+            Some(ui::create_spinner("Processing memories..."))
+        } else {
+            None
+        };
+
+        for operation_id in &operation_ids {
+            let failure = match client.poll_operation(agent_id, operation_id, verbose) {
+                Ok((true, _)) => continue,
+                Ok((false, error_msg)) => error_msg.unwrap_or_else(|| "Unknown error".to_string()),
+                Err(e) => format!("{:#}", e),
+            };
+            failures.push(format!("Operation {}: {}", operation_id, failure));
+            failed_batches.extend(operation_batches.get(operation_id));
+        }
+
+        if let Some(mut sp) = poll_spinner {
+            sp.finish();
+        }
+    }
+
+    // Record files from batches that went through (in async mode: that were accepted)
+    // so the next run skips them
+    let (failed, retained) = partition_batches(&hashes, batch_size, &failed_batches);
+    // A file's parts are adjacent, so dedup leaves each failed file once
+    let mut failed_files: Vec<String> = failed.into_iter().map(|(key, _)| key.clone()).collect();
+    failed_files.dedup();
+    // A file split into parts only counts as retained if every part was
+    let retained: Vec<_> = retained.into_iter().filter(|(key, _)| !failed_files.contains(key)).collect();
+    for (key, hash) in &retained {
+        manifest.record(agent_id, key.clone(), hash.clone());
+    }
+    if !retained.is_empty() {
+        if let Err(e) = manifest.save() {
+            ui::print_error(&format!("{:#}", e));
+        }
+    }
+
+    summary.stored_count = items_count;
+    summary.batches = batch_count;
+    summary.interrupted = interrupted;
+    summary.operation_ids = operation_ids;
+    summary.failures = failures;
+    summary.failed_files = failed_files;
+    Ok(BankImport { summary, failed_batches: failed_batches.len(), not_submitted, cancelled: false })
+}
+
+// Helper function to split per-file entries into those in failed batches and the rest.
+// Entries are in upload order, so batch `i` holds entries `i * batch_size..`.
+fn partition_batches<'a, T>(entries: &'a [T], batch_size: usize, failed_batches: &BTreeSet<usize>) -> (Vec<&'a T>, Vec<&'a T>) {
+    let mut failed = Vec::new();
+    let mut retained = Vec::new();
+    for (batch, chunk) in entries.chunks(batch_size).enumerate() {
+        if failed_batches.contains(&batch) {
+            failed.extend(chunk);
+        } else {
+            retained.extend(chunk);
+        }
+    }
+    (failed, retained)
+}
+
+// Helper function for the size of a batch's content, which is what the upload bar counts
+fn batch_bytes(request: &RetainRequest) -> u64 {
+    request.items.iter().map(|item| item.content.len() as u64).sum()
+}
+
+// Helper function to submit retain requests from a bounded pool of worker threads.
+// Results come back in request order. Unless `keep_going` is set, workers stop picking up
+// new batches after a failure, and they always stop after Ctrl-C (in-flight requests
+// finish); batches that were never submitted come back as None.
+fn submit_batches(
+    client: &ApiClient,
+    agent_id: &str,
+    requests: &[RetainRequest],
+    concurrency: usize,
+    keep_going: bool,
+    max_consecutive_failures: usize,
+    verbose: Verbosity,
+    pb: &ProgressBar,
+) -> Vec<Option<Result<MemoryPutResult>>> {
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    // Failures since the last success; with --keep-going, too many in a row stop the
+    // import rather than hammering a server that's down
+    let consecutive_failures = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let mut results: Vec<Option<Result<MemoryPutResult>>> = requests.iter().map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.min(requests.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while !stop.load(Ordering::SeqCst) && !interrupt::is_interrupted() {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(request) = requests.get(i) else {
+                            break;
+                        };
+                        let result = client.retain(agent_id, request, true, verbose);
+                        if result.is_ok() {
+                            consecutive_failures.store(0, Ordering::SeqCst);
+                        } else {
+                            let streak = consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+                            if !keep_going || streak >= max_consecutive_failures {
+                                stop.store(true, Ordering::SeqCst);
+                            }
+                        }
+                        done.push((i, result));
+                        let count = finished.fetch_add(1, Ordering::SeqCst) + 1;
+                        pb.set_message(format!("Uploading batches ({}/{})", count, requests.len()));
+                        pb.inc(batch_bytes(request));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (i, result) in worker.join().expect("retain worker panicked") {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    results
+}
+
+pub fn delete(
+    client: &ApiClient,
+    agent_id: &str,
+    unit_id: &str,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Deleting memory unit..."))
+    } else {
+        None
+    };
+
+    let response = client.delete_memory(agent_id, unit_id, verbose);
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    match response {
+        Ok(result) => {
+            if output_format == OutputFormat::Pretty {
+                if result.success {
+                    ui::print_success("Memory unit deleted successfully");
+                } else {
+                    ui::print_error("Failed to delete memory unit");
+                }
+            } else {
+                output::print_output(&result, output_format)?;
+            }
+            Ok(())
+        }
+        Err(e) => Err(e)
+    }
+}
+
+pub fn import(
+    client: &ApiClient,
+    agent_id: &str,
+    path: PathBuf,
+    r#async: bool,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let json = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read import file: {}", path.display()))?;
+    let items = parse_import_records(&json)
+        .with_context(|| format!("Invalid import file: {}", path.display()))?;
 
-        pb.inc(1);
+    if items.is_empty() {
+        anyhow::bail!("No records found in {}", path.display());
     }
 
-    pb.finish_with_message("Files processed");
+    let spinner = if output_format == OutputFormat::Pretty {
+        Some(ui::create_spinner("Importing memories..."))
+    } else {
+        None
+    };
 
-    // Always use async mode for the API call
     let request = RetainRequest {
         items,
-        async_: true,
+        async_: r#async,
         document_tags: None,
     };
 
-    let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Submitting retain request..."))
-    } else {
-        None
-    };
-
-    let response = client.retain(agent_id, &request, true, verbose);
+    let response = client.retain(agent_id, &request, r#async, verbose);
 
     if let Some(mut sp) = spinner {
         sp.finish();
@@ -312,55 +2056,18 @@ pub fn retain_files(
 
     match response {
         Ok(result) => {
-            if r#async {
-                // User requested async mode - return immediately
-                if output_format == OutputFormat::Pretty {
-                    ui::print_success("Files queued for processing");
-                    println!("  Items: {}", result.items_count);
+            if output_format == OutputFormat::Pretty {
+                ui::print_success(&format!("Imported {} records from {}", request.items.len(), path.display()));
+                if result.is_async {
+                    println!("  Status: queued for background processing");
                     if let Some(op_id) = &result.operation_id {
                         println!("  Operation ID: {}", op_id);
                     }
                 } else {
-                    output::print_output(&result, output_format)?;
+                    println!("  Stored count: {}", result.items_count);
                 }
             } else {
-                // Poll until completion
-                if let Some(operation_id) = &result.operation_id {
-                    let poll_spinner = if output_format == OutputFormat::Pretty {
-                        Some(ui::create_spinner("Processing memories..."))
-                    } else {
-                        None
-                    };
-
-                    let (success, error_msg) = client.poll_operation(agent_id, operation_id, verbose)?;
-
-                    if let Some(mut sp) = poll_spinner {
-                        sp.finish();
-                    }
-
-                    if success {
-                        if output_format == OutputFormat::Pretty {
-                            ui::print_success("Files retained successfully");
-                            println!("  Items processed: {}", result.items_count);
-                        } else {
-                            output::print_output(&result, output_format)?;
-                        }
-                    } else {
-                        let msg = error_msg.unwrap_or_else(|| "Unknown error".to_string());
-                        if output_format == OutputFormat::Pretty {
-                            ui::print_error(&format!("Retain operation failed: {}", msg));
-                        }
-                        anyhow::bail!("Retain operation failed: {}", msg);
-                    }
-                } else {
-                    // No operation ID returned, shouldn't happen with async=true
-                    if output_format == OutputFormat::Pretty {
-                        ui::print_success("Files retained successfully");
-                        println!("  Items processed: {}", result.items_count);
-                    } else {
-                        output::print_output(&result, output_format)?;
-                    }
-                }
+                output::print_output(&result, output_format)?;
             }
             Ok(())
         }
@@ -368,40 +2075,200 @@ pub fn retain_files(
     }
 }
 
-pub fn delete(
+pub fn export(
     client: &ApiClient,
     agent_id: &str,
-    unit_id: &str,
-    verbose: bool,
+    out_dir: Option<PathBuf>,
+    ext: String,
+    jsonl_file: Option<PathBuf>,
+    force: bool,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if let Some(out_dir) = out_dir {
+        return export_documents(client, agent_id, &out_dir, &ext, force, verbose, output_format);
+    }
+    if let Some(path) = jsonl_file {
+        return export_jsonl(client, agent_id, &path, force, verbose, output_format);
+    }
+
+    // Pretty has no file representation, so it exports as JSON
+    let format = if output_format == OutputFormat::Pretty { OutputFormat::Json } else { output_format };
+
+    let memories = client.export_memories(agent_id, verbose, |fetched, total| {
+        if verbose.is_verbose() {
+            eprintln!("Fetched {} of {} memories", fetched, total);
+        }
+    })?;
+
+    // Honours the global --out flag
+    output::print_output(&memories, format)
+}
+
+/// Machine-readable outcome of `export --jsonl-file`
+#[derive(Debug, serde::Serialize)]
+struct JsonlExport {
+    path: String,
+    memories: usize,
+}
+
+// Helper function for `export --jsonl-file`: write each memory as a JSON line as soon
+// as its page arrives, so a bank of any size is never held in memory at once
+fn export_jsonl(
+    client: &ApiClient,
+    agent_id: &str,
+    path: &Path,
+    force: bool,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if !force && path.exists() {
+        anyhow::bail!("{} already exists (use --force to overwrite)", path.display());
+    }
+    let file = fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = io::BufWriter::new(file);
+
+    // The length is only known once the first page reports the total
+    let pb = ui::create_rate_progress_bar(0, "Exporting memories");
+    let mut written = 0;
+    let result = client.for_each_memory_page(agent_id, verbose, |page, total| {
+        pb.set_length(total.max(0) as u64);
+        for memory in &page {
+            serde_json::to_writer(&mut writer, memory)?;
+            writer.write_all(b"\n")?;
+        }
+        written += page.len();
+        pb.set_position(written as u64);
+        Ok(())
+    });
+    let result = result.and_then(|_| writer.flush().map_err(anyhow::Error::from));
+    pb.finish_and_clear();
+    result.with_context(|| format!("Export to {} stopped after {} memories", path.display(), written))?;
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Exported {} memories to {}", written, path.display()));
+    } else {
+        let summary = JsonlExport { path: path.display().to_string(), memories: written };
+        output::print_output(&summary, output_format)?;
+    }
+    Ok(())
+}
+
+/// Machine-readable outcome of `export --out-dir`
+#[derive(Debug, serde::Serialize)]
+struct DirectoryExport {
+    out_dir: String,
+    files: Vec<String>,
+}
+
+// Helper function to turn a document ID into a file name that stays inside the
+// export directory: anything but letters, digits, '-', '_' and '.' becomes '_'
+fn document_file_name(document_id: &str, ext: &str) -> String {
+    let stem: String = document_id
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    let stem = stem.trim_start_matches('.');
+    format!("{}.{}", if stem.is_empty() { "document" } else { stem }, ext)
+}
+
+// Helper function to map each document to the first context among its memories,
+// since documents don't store one themselves
+fn document_contexts(memories: &[serde_json::Map<String, serde_json::Value>]) -> HashMap<String, String> {
+    let mut contexts = HashMap::new();
+    for memory in memories {
+        let document_id = memory.get("document_id").and_then(|v| v.as_str());
+        let context = memory.get("context").and_then(|v| v.as_str()).filter(|c| !c.trim().is_empty());
+        if let (Some(document_id), Some(context)) = (document_id, context) {
+            contexts.entry(document_id.to_string()).or_insert_with(|| context.to_string());
+        }
+    }
+    contexts
+}
+
+// Helper function to prefix a document's text with front matter, which is how
+// `retain-files` reads context and date back from Markdown files
+fn render_document_file(text: &str, front_matter: &FrontMatter) -> Result<String> {
+    if *front_matter == FrontMatter::default() {
+        return Ok(text.to_string());
+    }
+    Ok(format!("---\n{}---\n{}", serde_yaml::to_string(front_matter)?, text))
+}
+
+// Helper function for `export --out-dir`: write each document's original text to
+// its own file, so a notes folder can be retained again with `retain-files`
+fn export_documents(
+    client: &ApiClient,
+    agent_id: &str,
+    out_dir: &Path,
+    ext: &str,
+    force: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
+    let markdown = ext == "md";
+
     let spinner = if output_format == OutputFormat::Pretty {
-        Some(ui::create_spinner("Deleting memory unit..."))
+        Some(ui::create_spinner("Listing documents..."))
     } else {
         None
     };
-
-    let response = client.delete_memory(agent_id, unit_id, verbose);
-
+    let listing = client.list_all_document_ids(agent_id, verbose).and_then(|ids| {
+        // Only Markdown files carry front matter, so plain text skips the memory scan
+        let contexts = if markdown {
+            document_contexts(&client.export_memories(agent_id, verbose, |_, _| {})?)
+        } else {
+            HashMap::new()
+        };
+        Ok((ids, contexts))
+    });
     if let Some(mut sp) = spinner {
         sp.finish();
     }
-
-    match response {
-        Ok(result) => {
-            if output_format == OutputFormat::Pretty {
-                if result.success {
-                    ui::print_success("Memory unit deleted successfully");
-                } else {
-                    ui::print_error("Failed to delete memory unit");
-                }
-            } else {
-                output::print_output(&result, output_format)?;
-            }
-            Ok(())
+    let (document_ids, contexts) = listing?;
+
+    // Check every target before writing anything, so a refused run leaves no partial export
+    let mut file_names = HashSet::new();
+    let mut targets = Vec::new();
+    for document_id in &document_ids {
+        let file_name = document_file_name(document_id, ext);
+        if !file_names.insert(file_name.clone()) {
+            anyhow::bail!("Document {} maps to the file name {}, which another document already uses", document_id, file_name);
         }
-        Err(e) => Err(e)
+        let path = out_dir.join(&file_name);
+        if !force && path.exists() {
+            anyhow::bail!("{} already exists (use --force to overwrite)", path.display());
+        }
+        targets.push((document_id, path));
+    }
+    fs::create_dir_all(out_dir).with_context(|| format!("Failed to create directory {}", out_dir.display()))?;
+
+    let pb = ui::create_progress_bar(targets.len() as u64, "Exporting documents");
+    let mut files = Vec::new();
+    for (document_id, path) in targets {
+        let document = client.get_document(agent_id, document_id, verbose)?;
+        let content = if markdown {
+            let front_matter = FrontMatter {
+                context: contexts.get(document_id).cloned(),
+                date: Some(document.created_at),
+            };
+            render_document_file(&document.original_text, &front_matter)?
+        } else {
+            document.original_text
+        };
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        files.push(path.display().to_string());
+        pb.inc(1);
+    }
+    pb.finish_with_message("Documents exported");
+
+    if output_format == OutputFormat::Pretty {
+        ui::print_success(&format!("Exported {} documents to {}", files.len(), out_dir.display()));
+    } else {
+        let summary = DirectoryExport { out_dir: out_dir.display().to_string(), files };
+        output::print_output(&summary, output_format)?;
     }
+    Ok(())
 }
 
 pub fn clear(
@@ -409,7 +2276,7 @@ pub fn clear(
     agent_id: &str,
     fact_type: Option<String>,
     yes: bool,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     // Confirmation prompt unless -y flag is used
@@ -479,6 +2346,17 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    fn default_extensions() -> Vec<String> {
+        DEFAULT_TEXT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_fact_types() {
+        assert_eq!(expand_fact_types(&[FactType::All]), vec!["world", "experience", "opinion"]);
+        assert_eq!(expand_fact_types(&[FactType::Opinion, FactType::World]), vec!["opinion", "world"]);
+        assert_eq!(expand_fact_types(&[FactType::World, FactType::All]), vec!["world", "experience", "opinion"]);
+    }
+
     #[test]
     fn test_is_text_file_supported_extensions() {
         let supported = [
@@ -487,7 +2365,7 @@ mod tests {
         ];
         for filename in supported {
             assert!(
-                is_text_file(Path::new(filename)),
+                is_text_file(Path::new(filename), &default_extensions()),
                 "{} should be recognized as a text file",
                 filename
             );
@@ -496,10 +2374,10 @@ mod tests {
 
     #[test]
     fn test_is_text_file_case_insensitive() {
-        assert!(is_text_file(Path::new("file.JSON")));
-        assert!(is_text_file(Path::new("file.TXT")));
-        assert!(is_text_file(Path::new("file.Md")));
-        assert!(is_text_file(Path::new("file.YAML")));
+        assert!(is_text_file(Path::new("file.JSON"), &default_extensions()));
+        assert!(is_text_file(Path::new("file.TXT"), &default_extensions()));
+        assert!(is_text_file(Path::new("file.Md"), &default_extensions()));
+        assert!(is_text_file(Path::new("file.YAML"), &default_extensions()));
     }
 
     #[test]
@@ -510,7 +2388,7 @@ mod tests {
         ];
         for filename in unsupported {
             assert!(
-                !is_text_file(Path::new(filename)),
+                !is_text_file(Path::new(filename), &default_extensions()),
                 "{} should NOT be recognized as a text file",
                 filename
             );
@@ -519,16 +2397,485 @@ mod tests {
 
     #[test]
     fn test_is_text_file_no_extension() {
-        assert!(!is_text_file(Path::new("README")));
-        assert!(!is_text_file(Path::new("Makefile")));
-        assert!(!is_text_file(Path::new(".gitignore")));
+        assert!(!is_text_file(Path::new("README"), &default_extensions()));
+        assert!(!is_text_file(Path::new("Makefile"), &default_extensions()));
+        assert!(!is_text_file(Path::new(".gitignore"), &default_extensions()));
+    }
+
+    #[test]
+    fn test_is_text_file_custom_extensions() {
+        let extensions = normalize_extensions(vec![
+            "markdown".to_string(),
+            ".ORG".to_string(),
+            " text ".to_string(),
+        ]);
+        assert_eq!(extensions, vec!["markdown", "org", "text"]);
+        assert!(is_text_file(Path::new("notes.markdown"), &extensions));
+        assert!(is_text_file(Path::new("notes.org"), &extensions));
+        assert!(is_text_file(Path::new("notes.TEXT"), &extensions));
+        assert!(!is_text_file(Path::new("notes.md"), &extensions));
+    }
+
+    #[test]
+    fn test_normalize_extensions_drops_empty() {
+        let extensions = normalize_extensions(vec!["".to_string(), ".".to_string(), "md".to_string()]);
+        assert_eq!(extensions, vec!["md"]);
     }
 
     #[test]
     fn test_is_text_file_with_path() {
-        assert!(is_text_file(Path::new("/some/path/to/file.json")));
-        assert!(is_text_file(Path::new("../relative/path/file.md")));
-        assert!(!is_text_file(Path::new("/path/to/image.png")));
+        assert!(is_text_file(Path::new("/some/path/to/file.json"), &default_extensions()));
+        assert!(is_text_file(Path::new("../relative/path/file.md"), &default_extensions()));
+        assert!(!is_text_file(Path::new("/path/to/image.png"), &default_extensions()));
+    }
+
+    #[test]
+    fn test_read_content_arg_passthrough() {
+        let content = read_content_arg("Alice works at Google".to_string(), io::empty()).unwrap();
+        assert_eq!(content, "Alice works at Google");
+    }
+
+    #[test]
+    fn test_read_content_arg_from_reader() {
+        let input = "line one\nline two\n".as_bytes();
+        let content = read_content_arg("-".to_string(), input).unwrap();
+        assert_eq!(content, "line one\nline two\n");
+    }
+
+    #[test]
+    fn test_read_content_arg_empty_reader() {
+        assert!(read_content_arg("-".to_string(), io::empty()).is_err());
+        assert!(read_content_arg("-".to_string(), " \n\t".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_content_file() {
+        let path = std::env::temp_dir().join(format!("hindsight-cli-test-retain-{}.md", std::process::id()));
+        fs::write(&path, "# Notes\nAlice leads the AI team\n").unwrap();
+        assert_eq!(read_content_file(&path).unwrap(), "# Notes\nAlice leads the AI team\n");
+        fs::write(&path, "\n").unwrap();
+        let empty = read_content_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(empty.is_err());
+        assert!(read_content_file(Path::new("/nonexistent/hindsight-note.md")).is_err());
+    }
+
+    #[test]
+    fn test_clipboard_content() {
+        assert_eq!(clipboard_content("Alice prefers tea\n\n").unwrap(), "Alice prefers tea");
+        assert_eq!(clipboard_content("  indented\tnote \r\n").unwrap(), "  indented\tnote");
+        assert!(clipboard_content(" \n\t").unwrap_err().to_string().contains("empty"));
+    }
+
+    #[test]
+    fn test_pair_contexts() {
+        assert_eq!(pair_contexts(2, vec![]).unwrap(), vec![None, None]);
+        assert_eq!(
+            pair_contexts(2, vec!["work".to_string()]).unwrap(),
+            vec![Some("work".to_string()), Some("work".to_string())]
+        );
+        assert_eq!(
+            pair_contexts(2, vec!["work".to_string(), "home".to_string()]).unwrap(),
+            vec![Some("work".to_string()), Some("home".to_string())]
+        );
+        assert!(pair_contexts(3, vec!["a".to_string(), "b".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parent_dir_name() {
+        assert_eq!(parent_dir_name(Path::new("docs/project-x/plan.md")).as_deref(), Some("project-x"));
+        assert_eq!(parent_dir_name(Path::new("plan.md")), None);
+    }
+
+    #[test]
+    fn test_recall_counts() {
+        let results: Vec<crate::api::RecallResult> = serde_json::from_value(serde_json::json!([
+            { "id": "1", "text": "Alice hikes", "type": "world" },
+            { "id": "2", "text": "I met Alice", "type": "experience" },
+            { "id": "3", "text": "Alice moved", "type": "world" },
+            { "id": "4", "text": "Untyped" },
+        ]))
+        .unwrap();
+        let counts = recall_counts(&results);
+        assert_eq!(counts.total, 4);
+        let by_type = BTreeMap::from([("experience".to_string(), 1), ("unknown".to_string(), 1), ("world".to_string(), 2)]);
+        assert_eq!(counts.counts, by_type);
+        assert_eq!(
+            serde_json::to_value(recall_counts::<crate::api::RecallResult>(&[])).unwrap(),
+            serde_json::json!({ "counts": {}, "total": 0 })
+        );
+    }
+
+    #[test]
+    fn test_bank_for_path() {
+        let root = Path::new("notes");
+        assert_eq!(bank_for_path(Path::new("notes/alice/2024/plan.md"), root, 1).as_deref(), Some("alice"));
+        assert_eq!(bank_for_path(Path::new("notes/alice/2024/plan.md"), root, 2).as_deref(), Some("2024"));
+        // Files directly in the root, or not nested deep enough, have no bank
+        assert_eq!(bank_for_path(Path::new("notes/plan.md"), root, 1), None);
+        assert_eq!(bank_for_path(Path::new("notes/alice/plan.md"), root, 2), None);
+    }
+
+    #[test]
+    fn test_glob_root() {
+        assert!(has_glob_metachars(Path::new("notes/**/*.md")));
+        assert!(!has_glob_metachars(Path::new("notes/2024")));
+        assert_eq!(glob_root(Path::new("notes/2024/**/*.md")), PathBuf::from("notes/2024"));
+        assert_eq!(glob_root(Path::new("*.txt")), PathBuf::new());
+    }
+
+    #[test]
+    fn test_document_file_name() {
+        assert_eq!(document_file_name("meeting-2024-03-01", "md"), "meeting-2024-03-01.md");
+        assert_eq!(document_file_name("notes/../plan", "txt"), "notes_.._plan.txt");
+        assert_eq!(document_file_name("..", "md"), "document.md");
+    }
+
+    #[test]
+    fn test_document_contexts_takes_first_context() {
+        let memories: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(
+            r#"[
+                {"document_id": "a", "context": ""},
+                {"document_id": "a", "context": "team sync"},
+                {"document_id": "a", "context": "other"},
+                {"document_id": null, "context": "orphan"}
+            ]"#,
+        )
+        .unwrap();
+        let contexts = document_contexts(&memories);
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts["a"], "team sync");
+    }
+
+    #[test]
+    fn test_render_document_file_round_trips_front_matter() {
+        let front_matter = FrontMatter {
+            context: Some("team meeting".to_string()),
+            date: Some("2024-03-05T10:00:00Z".to_string()),
+        };
+        let file = render_document_file("Alice presented.\n", &front_matter).unwrap();
+        assert_eq!(split_front_matter(&file), (front_matter, "Alice presented.\n"));
+        assert_eq!(render_document_file("Plain", &FrontMatter::default()).unwrap(), "Plain");
+    }
+
+    #[test]
+    fn test_parse_queries_skips_blank_lines() {
+        assert_eq!(
+            parse_queries("What does Alice do?\r\n\n  hiking plans  \n"),
+            vec!["What does Alice do?", "hiking plans"]
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let vars = template_vars("Alice leads the AI team.", "notes", "Who is Alice?", 2);
+        assert_eq!(
+            render_template("> {text}\n-- {bank}: {query} ({mental_model_count}) {{literal}}", &vars).unwrap(),
+            "> Alice leads the AI team.\n-- notes: Who is Alice? (2) {literal}"
+        );
+        assert!(render_template("{opinions}", &vars).is_err());
+        assert!(render_template("{text", &vars).is_err());
+        assert!(render_template("text}", &vars).is_err());
+    }
+
+    #[test]
+    fn test_is_large_import() {
+        assert!(!is_large_import(100, 1 << 20));
+        assert!(is_large_import(101, 0));
+        assert!(is_large_import(3, 11 << 20));
+    }
+
+    #[test]
+    fn test_chunk_content_prefers_boundaries() {
+        // Paragraph break beats a later sentence end
+        let content = "First paragraph.\n\nSecond one. It goes on";
+        assert_eq!(chunk_content(content, 30), vec!["First paragraph.", "Second one. It goes on"]);
+        // Then sentence ends, then spaces, then a hard cut
+        assert_eq!(chunk_content("One. Two three four", 12), vec!["One.", "Two three", "four"]);
+        assert_eq!(chunk_content("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        // Short content is left alone
+        assert_eq!(chunk_content("  short  ", 100), vec!["short"]);
+    }
+
+    #[test]
+    fn test_chunk_item_numbers_parts() {
+        let item = MemoryItem {
+            content: "Alpha beta. Gamma delta.".to_string(),
+            context: Some("notes.md".to_string()),
+            metadata: None,
+            timestamp: None,
+            document_id: Some("notes".to_string()),
+            entities: None,
+            tags: None,
+        };
+        let parts = chunk_item(item.clone(), Some(12));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].content, "Alpha beta.");
+        assert_eq!(parts[1].context.as_deref(), Some("notes.md (part 2 of 2)"));
+        assert_eq!(parts[1].document_id.as_deref(), Some("notes"));
+        assert_eq!(chunk_item(item, None).len(), 1);
+    }
+
+    #[test]
+    fn test_read_query_arg_trims_trailing_newline() {
+        let query = read_query_arg("-".to_string(), "What does Alice do?\r\n".as_bytes()).unwrap();
+        assert_eq!(query, "What does Alice do?");
+
+        let query = read_query_arg("hiking recommendations".to_string(), io::empty()).unwrap();
+        assert_eq!(query, "hiking recommendations");
+    }
+
+    #[test]
+    fn test_document_ids_for_unique_stems() {
+        let root = Path::new("notes");
+        let files = vec![
+            PathBuf::from("notes/alice.md"),
+            PathBuf::from("notes/work/bob.txt"),
+        ];
+        assert_eq!(document_ids_for(&files, root), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_document_ids_for_colliding_stems() {
+        let root = Path::new("notes");
+        let files = vec![
+            PathBuf::from("notes/2024/todo.md"),
+            PathBuf::from("notes/2025/todo.md"),
+            PathBuf::from("notes/readme.md"),
+        ];
+        assert_eq!(
+            document_ids_for(&files, root),
+            vec!["2024/todo", "2025/todo", "readme"]
+        );
+    }
+
+    #[test]
+    fn test_split_front_matter() {
+        let content = "---\ncontext: team meeting\ndate: 2024-03-05\n---\n# Notes\nAlice presented.\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter.context, Some("team meeting".to_string()));
+        assert_eq!(front_matter.date, Some("2024-03-05".to_string()));
+        assert_eq!(body, "# Notes\nAlice presented.\n");
+    }
+
+    #[test]
+    fn test_split_front_matter_crlf() {
+        let content = "---\r\ncontext: standup\r\n---\r\nBody\r\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter.context, Some("standup".to_string()));
+        assert_eq!(body, "Body\r\n");
+    }
+
+    #[test]
+    fn test_split_front_matter_absent() {
+        let content = "# Notes\n---\nNot front matter\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter, FrontMatter::default());
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_split_front_matter_unclosed() {
+        let content = "---\ncontext: never closed\nBody text\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter, FrontMatter::default());
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_split_front_matter_invalid_yaml() {
+        let content = "---\ncontext: [unclosed\n---\nBody\n";
+        let (front_matter, body) = split_front_matter(content);
+        assert_eq!(front_matter, FrontMatter::default());
+        assert_eq!(body, content);
+    }
+
+    #[test]
+    fn test_split_front_matter_empty_block() {
+        let (front_matter, body) = split_front_matter("---\n---\nBody\n");
+        assert_eq!(front_matter, FrontMatter::default());
+        assert_eq!(body, "Body\n");
+    }
+
+    #[test]
+    fn test_parse_date_value() {
+        let date = parse_date_value("2024-03-05").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-03-05T00:00:00+00:00");
+        let date = parse_date_value("2024-03-05T10:30:00+02:00").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-03-05T08:30:00+00:00");
+        assert!(parse_date_value("last tuesday").is_none());
+    }
+
+    #[test]
+    fn test_parse_bank_ids() {
+        assert_eq!(parse_bank_ids("alice").unwrap(), vec!["alice"]);
+        assert_eq!(parse_bank_ids("alice, bob,,carol").unwrap(), vec!["alice", "bob", "carol"]);
+        assert!(parse_bank_ids(" , ").is_err());
+    }
+
+    #[test]
+    fn test_context_matches() {
+        assert!(context_matches(Some("Meeting-2024 standup"), "meeting-2024"));
+        assert!(context_matches(Some("team meeting"), "MEETING"));
+        assert!(!context_matches(Some("hiking trip"), "meeting"));
+        assert!(!context_matches(None, "meeting"));
+    }
+
+    #[test]
+    fn test_date_window_bounds_are_inclusive() {
+        let window = DateWindow::parse(Some("2024-01-01"), Some("2024-01-31")).unwrap();
+        assert!(window.contains(Some("2024-01-01T00:00:00Z")));
+        assert!(window.contains(Some("2024-01-31T23:30:00+00:00")));
+        assert!(!window.contains(Some("2024-02-01T00:00:00Z")));
+        assert!(!window.contains(Some("2023-12-31T23:59:59Z")));
+        assert!(!window.contains(None));
+        assert!(!window.contains(Some("not a date")));
+    }
+
+    #[test]
+    fn test_date_window_open_ended() {
+        let window = DateWindow::parse(Some("2024-06-01T12:00:00Z"), None).unwrap();
+        assert!(window.is_set());
+        assert!(window.contains(Some("2030-01-01T00:00:00Z")));
+        assert!(!window.contains(Some("2024-06-01T11:59:59Z")));
+        assert!(!DateWindow::parse(None, None).unwrap().is_set());
+    }
+
+    #[test]
+    fn test_date_window_errors_name_the_flag() {
+        let err = DateWindow::parse(None, Some("last tuesday")).unwrap_err().to_string();
+        assert!(err.contains("--until"), "{}", err);
+        assert!(err.contains("last tuesday"), "{}", err);
+        let err = DateWindow::parse(Some("2024-02-01"), Some("2024-01-01")).unwrap_err().to_string();
+        assert!(err.contains("--since"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_file_size() {
+        assert_eq!(parse_file_size("1048576"), Ok(1048576));
+        assert_eq!(parse_file_size("5MB"), Ok(5 * 1024 * 1024));
+        assert_eq!(parse_file_size("500k"), Ok(500 * 1024));
+        assert_eq!(parse_file_size("2 GB"), Ok(2 * 1024 * 1024 * 1024));
+        assert!(parse_file_size("5TB").is_err());
+        assert!(parse_file_size("MB").is_err());
+        assert!(parse_file_size("").is_err());
+    }
+
+    #[test]
+    fn test_format_file_size() {
+        assert_eq!(format_file_size(512), "512 B");
+        assert_eq!(format_file_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_file_size(1536), "1.5 KB");
+    }
+
+    #[test]
+    fn test_read_text_file_skips_large_and_binary_files() {
+        let dir = std::env::temp_dir().join(format!("hindsight-cli-test-read-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let text = dir.join("note.txt");
+        let binary = dir.join("image.txt");
+        fs::write(&text, "Alice likes tea").unwrap();
+        fs::write(&binary, [0x48, 0x69, 0xff, 0xfe, 0x00]).unwrap();
+
+        let read_text = read_text_file(&text, 1024).unwrap();
+        let read_large = read_text_file(&text, 4).unwrap();
+        let read_binary = read_text_file(&binary, 1024).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(read_text, FileContent::Text("Alice likes tea".to_string()));
+        assert!(matches!(read_large, FileContent::Skipped(reason) if reason.contains("--max-file-size")));
+        assert!(matches!(read_binary, FileContent::Skipped(reason) if reason.contains("UTF-8")));
+    }
+
+    #[test]
+    fn test_partition_batches() {
+        let files = ["a", "b", "c", "d", "e"];
+        let failed_batches = BTreeSet::from([1]);
+        let (failed, retained) = partition_batches(&files, 2, &failed_batches);
+        assert_eq!(failed, vec![&"c", &"d"]);
+        assert_eq!(retained, vec![&"a", &"b", &"e"]);
+    }
+
+    #[test]
+    fn test_retain_output_includes_document_id() {
+        let result = MemoryPutResult {
+            success: true,
+            items_count: 1,
+            message: "Stored 1 memory units".to_string(),
+            is_async: false,
+            operation_id: None,
+        };
+        let json = serde_json::to_value(RetainOutput { document_id: "meeting-2024-03-01", result: &result }).unwrap();
+        assert_eq!(json["document_id"], "meeting-2024-03-01");
+        assert_eq!(json["items_count"], 1);
+        assert_eq!(json["success"], true);
+    }
+
+    #[test]
+    fn test_import_summary_serialize() {
+        let summary = ImportSummary {
+            files_found: 3,
+            files_read: 3,
+            skipped_unchanged: 0,
+            skipped_files: vec![],
+            stored_count: 12,
+            batches: 1,
+            is_async: true,
+            interrupted: false,
+            operation_ids: vec!["op-1".to_string()],
+            failures: vec![],
+            failed_files: vec![],
+        };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["files_found"], 3);
+        assert_eq!(json["files_read"], 3);
+        assert_eq!(json["stored_count"], 12);
+        assert_eq!(json["interrupted"], false);
+        assert_eq!(json["operation_ids"][0], "op-1");
+    }
+
+    #[test]
+    fn test_parse_import_records() {
+        let json = r#"[
+            {"content": "Alice works at Google", "context": "work", "timestamp": "2024-03-05"},
+            {"text": "Bob likes hiking", "context": "", "date": "", "id": "abc", "fact_type": "world"}
+        ]"#;
+        let items = parse_import_records(json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "Alice works at Google");
+        assert_eq!(items[0].context.as_deref(), Some("work"));
+        assert_eq!(items[0].timestamp.unwrap().to_rfc3339(), "2024-03-05T00:00:00+00:00");
+        assert_eq!(items[1].content, "Bob likes hiking");
+        assert!(items[1].context.is_none());
+        assert!(items[1].timestamp.is_none());
+    }
+
+    #[test]
+    fn test_parse_import_records_reports_location() {
+        let err = parse_import_records("[\n  {\"context\": \"work\"}\n]").unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("missing field `content`"), "{}", message);
+        assert!(message.contains("line 2"), "{}", message);
+
+        let err = parse_import_records(r#"{"content": "not an array"}"#).unwrap_err();
+        assert!(format!("{:#}", err).contains("JSON array"));
+    }
+
+    #[test]
+    fn test_parse_import_records_rejects_bad_records() {
+        let err = parse_import_records(r#"[{"content": "ok"}, {"content": "  "}]"#).unwrap_err();
+        assert!(err.to_string().contains("Record 1"));
+
+        let err = parse_import_records(r#"[{"content": "ok", "timestamp": "someday"}]"#).unwrap_err();
+        assert!(err.to_string().contains("Record 0"));
+    }
+
+    #[test]
+    fn test_is_markdown_file() {
+        assert!(is_markdown_file(Path::new("notes.md")));
+        assert!(is_markdown_file(Path::new("notes.MD")));
+        assert!(is_markdown_file(Path::new("notes.markdown")));
+        assert!(!is_markdown_file(Path::new("notes.txt")));
     }
 
     #[test]
@@ -4,3 +4,7 @@ pub mod document;
 pub mod entity;
 pub mod operation;
 pub mod explore;
+pub mod health;
+pub mod config;
+pub mod history;
+pub mod cache;
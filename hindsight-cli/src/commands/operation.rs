@@ -1,12 +1,14 @@
 use anyhow::Result;
-use crate::api::ApiClient;
+use std::thread;
+use std::time::Duration;
+use crate::api::{ApiClient, OperationStatus, Verbosity};
 use crate::output::{self, OutputFormat};
 use crate::ui;
 
 pub fn list(
     client: &ApiClient,
     agent_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -47,11 +49,81 @@ pub fn list(
     }
 }
 
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn status(
+    client: &ApiClient,
+    agent_id: &str,
+    operation_id: &str,
+    wait: bool,
+    verbose: Verbosity,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let spinner = if output_format == OutputFormat::Pretty {
+        let message = if wait { "Waiting for operation..." } else { "Fetching operation status..." };
+        Some(ui::create_spinner(message))
+    } else {
+        None
+    };
+
+    let response = loop {
+        match client.get_operation_status(agent_id, operation_id, verbose) {
+            Ok(status) if wait && !status.is_terminal() => thread::sleep(STATUS_POLL_INTERVAL),
+            other => break other,
+        }
+    };
+
+    if let Some(mut sp) = spinner {
+        sp.finish();
+    }
+
+    match response {
+        Ok(status) => {
+            if output_format == OutputFormat::Pretty {
+                print_status(&status);
+            } else {
+                output::print_output(&status, output_format)?;
+            }
+
+            if wait && status.status == "failed" {
+                anyhow::bail!("Operation {} failed", status.operation_id);
+            }
+            Ok(())
+        }
+        Err(e) => Err(e)
+    }
+}
+
+fn print_status(status: &OperationStatus) {
+    match status.status.as_str() {
+        "completed" => ui::print_success(&format!("Operation {} completed", status.operation_id)),
+        "failed" => ui::print_error(&format!("Operation {} failed", status.operation_id)),
+        "not_found" => ui::print_warning(&format!("Operation {} not found", status.operation_id)),
+        _ => ui::print_info(&format!("Operation {} is {}", status.operation_id, status.status)),
+    }
+
+    if let Some(operation_type) = &status.operation_type {
+        println!("  Type: {}", operation_type);
+    }
+    if let Some(created_at) = &status.created_at {
+        println!("  Created: {}", created_at);
+    }
+    if let Some(updated_at) = &status.updated_at {
+        println!("  Updated: {}", updated_at);
+    }
+    if let Some(completed_at) = &status.completed_at {
+        println!("  Completed: {}", completed_at);
+    }
+    if let Some(error_message) = &status.error_message {
+        println!("  Error: {}", error_message);
+    }
+}
+
 pub fn cancel(
     client: &ApiClient,
     agent_id: &str,
     operation_id: &str,
-    verbose: bool,
+    verbose: Verbosity,
     output_format: OutputFormat,
 ) -> Result<()> {
     let spinner = if output_format == OutputFormat::Pretty {
@@ -0,0 +1,137 @@
+//! Shell completion scripts, with bank IDs completed from the server
+//!
+//! `hindsight completions <shell>` prints clap's static script. For bash and zsh it
+//! also appends a small wrapper that completes the bank ID positional by running the
+//! hidden `hindsight __complete-banks` command. Bank IDs are cached for a short
+//! while so completing doesn't hit the network on every keypress.
+
+use anyhow::Result;
+use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CACHE_DIR_NAME: &str = "hindsight";
+const CACHE_FILE_NAME: &str = "bank-ids.json";
+
+/// How long a cached bank list is used before asking the server again
+pub const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Command groups whose subcommands take the bank ID as their first positional
+const BANK_ID_GROUPS: &str = "memory|bank|document|entity|operation";
+
+/// Bank IDs fetched for completion, tagged with the server they came from
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct BankCache {
+    api_url: String,
+    /// Unix time in seconds when the list was fetched
+    fetched_at: u64,
+    bank_ids: Vec<String>,
+}
+
+impl BankCache {
+    fn is_fresh(&self, api_url: &str, now: u64) -> bool {
+        self.api_url == api_url && now.saturating_sub(self.fetched_at) < CACHE_TTL.as_secs()
+    }
+}
+
+/// Cache file location (e.g. ~/.cache/hindsight/bank-ids.json on Linux)
+pub fn cache_file_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(CACHE_DIR_NAME).join(CACHE_FILE_NAME))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Bank IDs cached for `api_url`, if they were fetched within [`CACHE_TTL`]
+pub fn cached_bank_ids(path: &Path, api_url: &str) -> Option<Vec<String>> {
+    let content = fs::read_to_string(path).ok()?;
+    let cache: BankCache = serde_json::from_str(&content).ok()?;
+    cache.is_fresh(api_url, now_secs()).then_some(cache.bank_ids)
+}
+
+/// Remember the bank IDs just fetched from `api_url`
+pub fn store_bank_ids(path: &Path, api_url: &str, bank_ids: &[String]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cache = BankCache {
+        api_url: api_url.to_string(),
+        fetched_at: now_secs(),
+        bank_ids: bank_ids.to_vec(),
+    };
+    fs::write(path, serde_json::to_string(&cache)?)?;
+    Ok(())
+}
+
+/// Extra script appended to clap's output so the bank ID positional completes to
+/// real bank IDs. Only the word right after `<group> <subcommand>` is completed, so
+/// global flags placed before the group fall back to the static completion.
+pub fn dynamic_bank_completion(shell: Shell, bin: &str) -> Option<String> {
+    match shell {
+        Shell::Bash => Some(format!(
+            r#"
+_{bin}_with_banks() {{
+    _{bin} "$@"
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    if [[ $COMP_CWORD -eq 3 && "$cur" != -* && "${{COMP_WORDS[1]}}" =~ ^({groups})$ ]]; then
+        COMPREPLY=( $(compgen -W "$({bin} __complete-banks 2>/dev/null)" -- "$cur") )
+    fi
+}}
+complete -F _{bin}_with_banks -o nosort -o bashdefault -o default {bin}
+"#,
+            bin = bin,
+            groups = BANK_ID_GROUPS,
+        )),
+        Shell::Zsh => Some(format!(
+            r#"
+_{bin}_with_banks() {{
+    if (( CURRENT == 4 )) && [[ "${{words[2]}}" == ({groups}) && "${{words[CURRENT]}}" != -* ]]; then
+        local -a banks
+        banks=(${{(f)"$({bin} __complete-banks 2>/dev/null)"}})
+        compadd -a banks && return
+    fi
+    _{bin} "$@"
+}}
+compdef _{bin}_with_banks {bin}
+"#,
+            bin = bin,
+            groups = BANK_ID_GROUPS,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_freshness() {
+        let cache = BankCache {
+            api_url: "http://localhost:8888".to_string(),
+            fetched_at: 1_000,
+            bank_ids: vec!["notes".to_string()],
+        };
+        assert!(cache.is_fresh("http://localhost:8888", 1_030));
+        assert!(!cache.is_fresh("http://localhost:8888", 1_060));
+        // A list from another server is never reused
+        assert!(!cache.is_fresh("https://hindsight.example.com", 1_030));
+    }
+
+    #[test]
+    fn test_store_and_read_cache() {
+        let path = std::env::temp_dir()
+            .join(format!("hindsight-cli-test-completion-{}", std::process::id()))
+            .join(CACHE_FILE_NAME);
+        let banks = vec!["notes".to_string(), "work".to_string()];
+
+        assert_eq!(cached_bank_ids(&path, "http://localhost:8888"), None);
+        store_bank_ids(&path, "http://localhost:8888", &banks).unwrap();
+        assert_eq!(cached_bank_ids(&path, "http://localhost:8888"), Some(banks));
+        assert_eq!(cached_bank_ids(&path, "http://other:8888"), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,22 +1,250 @@
 use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::output::OutputFormat;
 
 const DEFAULT_API_URL: &str = "http://localhost:8888";
 const CONFIG_FILE_NAME: &str = "config";
 const CONFIG_DIR_NAME: &str = ".hindsight";
 
+/// Commented starter file written by `hindsight config init`
+pub const CONFIG_TEMPLATE: &str = r#"# Hindsight CLI configuration (TOML)
+# Each setting resolves as: command line flag > environment variable > this file > default.
+
+# API server to connect to (HINDSIGHT_API_URL overrides this)
+api_url = "http://localhost:8888"
+
+# API key, sent as a Bearer token (HINDSIGHT_API_KEY overrides this)
+# api_key = ""
+
+# Extra PEM root certificate to trust, e.g. an internal CA (same as --cacert; HINDSIGHT_CA_CERT overrides this)
+# ca_cert = "/etc/ssl/internal-ca.pem"
+
+# Bank used when a command's bank ID is omitted (HINDSIGHT_DEFAULT_BANK overrides this)
+# default_bank = "my-bank"
+
+# Default output format: pretty, json, json-compact, ndjson, yaml or toml (-o overrides
+# this; HINDSIGHT_OUTPUT_FORMAT overrides the file). Local commands that don't talk to the
+# server, like `history` and `config show`, still default to pretty.
+# output_format = "json"
+
+# Record recall/reflect queries for `hindsight history` (same as --no-history when false;
+# HINDSIGHT_HISTORY overrides this)
+# history = false
+
+# Connection pooling. Idle connections are kept open so later requests (explore,
+# multi-bank recall, retain-files batches) skip the TCP/TLS handshake. Defaults:
+# no limit on idle connections per host, and idle connections close after 90 seconds.
+# Raise the timeout on high-latency links; -v logs when a connection is reused.
+# HINDSIGHT_POOL_MAX_IDLE_PER_HOST and HINDSIGHT_POOL_IDLE_TIMEOUT override these.
+# pool_max_idle_per_host = 8
+# pool_idle_timeout = 300
+
+# Named profiles, selected with --profile <name> or HINDSIGHT_PROFILE
+# [profiles.dev]
+# api_url = "http://localhost:8888"
+#
+# [profiles.prod]
+# api_url = "https://hindsight.example.com"
+# api_key = ""
+"#;
+
 #[derive(Debug)]
 pub struct Config {
     pub api_url: String,
     pub api_key: Option<String>,
     pub source: ConfigSource,
+    /// Name of the active profile, if one was selected
+    pub profile: Option<String>,
+    /// Extra PEM root certificate to trust (internal CAs)
+    pub ca_cert: Option<PathBuf>,
+    /// Bank used when a command's bank ID is omitted
+    pub default_bank: Option<String>,
+    /// Record recall/reflect queries in the local history file
+    pub history: bool,
+    /// Most idle connections kept open per host (HTTP client default when `None`)
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle connection is kept open (HTTP client default when `None`)
+    pub pool_idle_timeout: Option<u64>,
+    /// Output format when `-o` isn't given
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Values supplied on the command line that take precedence over everything else.
+#[derive(Debug, Default, Clone)]
+pub struct ConfigOverrides {
+    /// `--api-url` flag
+    pub api_url: Option<String>,
+    /// `--config` flag, replacing the default config file location
+    pub config_path: Option<PathBuf>,
+    /// `--profile` flag (or HINDSIGHT_PROFILE), selecting a `[profiles.<name>]` table
+    pub profile: Option<String>,
+    /// `--cacert` flag
+    pub ca_cert: Option<PathBuf>,
+}
+
+/// Raw values read from a TOML config file. Every key is optional so the
+/// file only needs to set what it wants to change.
+#[derive(Debug, Default, Deserialize)]
+pub struct FileConfig {
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub ca_cert: Option<PathBuf>,
+    pub default_bank: Option<String>,
+    /// Set to false to stop recording queries in the local history
+    pub history: Option<bool>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<u64>,
+    /// Output format name, parsed with [`OutputFormat`]'s `FromStr`
+    pub output_format: Option<String>,
+    /// Named server profiles, e.g. `[profiles.dev]`
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+/// Settings for a single named profile in the config file.
+#[derive(Debug, Default, Deserialize)]
+pub struct ProfileConfig {
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub ca_cert: Option<PathBuf>,
+    pub default_bank: Option<String>,
+}
+
+impl FileConfig {
+    /// Read and parse a TOML config file.
+    /// Returns `Ok(None)` if the file does not exist; a malformed file is an error.
+    pub fn from_file(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        let mut file_config: FileConfig = match toml::from_str(&content) {
+            Ok(file_config) => file_config,
+            Err(e) => match Self::from_legacy_lines(&content) {
+                Some(file_config) => {
+                    eprintln!(
+                        "warning: {} is not valid TOML; reading api_url and api_key the old way. \
+                         Quote the values (api_url = \"http://...\"), as this will stop working in a future release.",
+                        path.display()
+                    );
+                    file_config
+                }
+                None => {
+                    return Err(e).with_context(|| format!("Failed to parse config file: {}", path.display()))
+                }
+            },
+        };
+
+        // Treat empty values as unset
+        file_config.api_url = file_config.api_url.filter(|v| !v.is_empty());
+        file_config.api_key = file_config.api_key.filter(|v| !v.is_empty());
+        file_config.ca_cert = file_config.ca_cert.filter(|v| !v.as_os_str().is_empty());
+        file_config.default_bank = file_config.default_bank.filter(|v| !v.is_empty());
+        file_config.output_format = file_config.output_format.filter(|v| !v.is_empty());
+        for profile in file_config.profiles.values_mut() {
+            profile.api_url = profile.api_url.take().filter(|v| !v.is_empty());
+            profile.api_key = profile.api_key.take().filter(|v| !v.is_empty());
+            profile.ca_cert = profile.ca_cert.take().filter(|v| !v.as_os_str().is_empty());
+            profile.default_bank = profile.default_bank.take().filter(|v| !v.is_empty());
+        }
+
+        Ok(Some(file_config))
+    }
+
+    /// The line-based format read before the config file was TOML, which allowed
+    /// unquoted values. Only `api_url` and `api_key` were read; `None` if neither is set.
+    fn from_legacy_lines(content: &str) -> Option<Self> {
+        let value = |key: &str| content.lines().rev().find_map(|line| parse_config_value(line, key));
+        let file_config = FileConfig { api_url: value("api_url"), api_key: value("api_key"), ..Default::default() };
+        (file_config.api_url.is_some() || file_config.api_key.is_some()).then_some(file_config)
+    }
+}
+
+/// Values read from `HINDSIGHT_*` environment variables, which rank between command
+/// line flags and the config file:
+///
+/// | Variable | Setting |
+/// |---|---|
+/// | `HINDSIGHT_API_URL` | `api_url` |
+/// | `HINDSIGHT_API_KEY` | `api_key` |
+/// | `HINDSIGHT_CA_CERT` | `ca_cert` |
+/// | `HINDSIGHT_DEFAULT_BANK` | `default_bank` |
+/// | `HINDSIGHT_HISTORY` | `history` |
+/// | `HINDSIGHT_POOL_MAX_IDLE_PER_HOST` | `pool_max_idle_per_host` |
+/// | `HINDSIGHT_POOL_IDLE_TIMEOUT` | `pool_idle_timeout` |
+/// | `HINDSIGHT_OUTPUT_FORMAT` | `output_format` |
+///
+/// `HINDSIGHT_PROFILE`, `HINDSIGHT_CONFIG`, `HINDSIGHT_TIMEOUT` and `HINDSIGHT_RETRIES`
+/// stand in for their flags and are read by clap.
+#[derive(Debug, Default, PartialEq)]
+pub struct EnvConfig {
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub ca_cert: Option<PathBuf>,
+    pub default_bank: Option<String>,
+    pub history: Option<bool>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<u64>,
+    pub output_format: Option<OutputFormat>,
+}
+
+impl EnvConfig {
+    /// Read the process environment
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Read each variable through `var`. Empty values count as unset, like empty
+    /// values in the config file; a number or boolean that doesn't parse is an error.
+    pub fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| var(name).filter(|v| !v.is_empty());
+        Ok(EnvConfig {
+            api_url: var("HINDSIGHT_API_URL"),
+            api_key: var("HINDSIGHT_API_KEY"),
+            ca_cert: var("HINDSIGHT_CA_CERT").map(PathBuf::from),
+            default_bank: var("HINDSIGHT_DEFAULT_BANK"),
+            history: var("HINDSIGHT_HISTORY").map(|v| parse_env_bool("HINDSIGHT_HISTORY", &v)).transpose()?,
+            pool_max_idle_per_host: var("HINDSIGHT_POOL_MAX_IDLE_PER_HOST")
+                .map(|v| parse_env_number("HINDSIGHT_POOL_MAX_IDLE_PER_HOST", &v))
+                .transpose()?,
+            pool_idle_timeout: var("HINDSIGHT_POOL_IDLE_TIMEOUT")
+                .map(|v| parse_env_number("HINDSIGHT_POOL_IDLE_TIMEOUT", &v))
+                .transpose()?,
+            output_format: var("HINDSIGHT_OUTPUT_FORMAT")
+                .map(|v| v.parse().context("Invalid HINDSIGHT_OUTPUT_FORMAT"))
+                .transpose()?,
+        })
+    }
+}
+
+fn parse_env_bool(name: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("Invalid {}: '{}' (expected true or false)", name, value),
+    }
+}
+
+fn parse_env_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid {}: '{}' (expected a whole number)", name, value))
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConfigSource {
+    CommandLine,
+    Profile(String),
     LocalFile,
     Environment,
     Default,
@@ -25,6 +253,8 @@ pub enum ConfigSource {
 impl std::fmt::Display for ConfigSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ConfigSource::CommandLine => write!(f, "command line flag"),
+            ConfigSource::Profile(name) => write!(f, "profile '{}'", name),
             ConfigSource::LocalFile => write!(f, "config file"),
             ConfigSource::Environment => write!(f, "environment variable"),
             ConfigSource::Default => write!(f, "default"),
@@ -34,32 +264,85 @@ impl std::fmt::Display for ConfigSource {
 
 impl Config {
     /// Load configuration with the following priority:
-    /// 1. Environment variable (HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority, for overrides
-    /// 2. Local config file (~/.hindsight/config.toml)
+    /// 1. Environment variables (see [`EnvConfig`]) - highest priority, for overrides
+    /// 2. Local config file (~/.hindsight/config)
     /// 3. Default (http://localhost:8888)
     pub fn load() -> Result<Self> {
-        // Load API key from environment (highest priority)
-        let env_api_key = env::var("HINDSIGHT_API_KEY").ok();
+        Self::load_with(&ConfigOverrides::default())
+    }
 
-        // 1. Environment variable takes highest priority (for overrides)
-        if let Ok(api_url) = env::var("HINDSIGHT_API_URL") {
-            return Self::validate_and_create(api_url, env_api_key, ConfigSource::Environment);
-        }
+    /// Load configuration, layering each value as:
+    /// command line flag > environment variable > config file > default.
+    ///
+    /// Selecting a profile is an explicit choice, so the profile's `api_url`
+    /// ranks just below `--api-url` and above HINDSIGHT_API_URL.
+    pub fn load_with(overrides: &ConfigOverrides) -> Result<Self> {
+        Self::load_layered(overrides, EnvConfig::from_env()?)
+    }
 
-        // 2. Try local config file
-        if let Some((api_url, file_api_key)) = Self::load_from_file()? {
-            // Environment api_key takes precedence over file api_key
-            let api_key = env_api_key.or(file_api_key);
-            return Self::validate_and_create(api_url, api_key, ConfigSource::LocalFile);
-        }
+    // The precedence rules for load_with, with the environment passed in so tests
+    // don't depend on (or change) the real one
+    fn load_layered(overrides: &ConfigOverrides, env: EnvConfig) -> Result<Self> {
+        let config_path = overrides.config_path.clone().or_else(Self::config_file_path);
+        let mut file_config = match config_path {
+            Some(path) => FileConfig::from_file(&path)?.unwrap_or_default(),
+            None => FileConfig::default(),
+        };
 
-        // 3. Fall back to default
-        Self::validate_and_create(DEFAULT_API_URL.to_string(), env_api_key, ConfigSource::Default)
-    }
+        let profile = match &overrides.profile {
+            Some(name) => match file_config.profiles.remove(name) {
+                Some(profile) => profile,
+                None => {
+                    let mut available: Vec<_> = file_config.profiles.keys().cloned().collect();
+                    available.sort();
+                    if available.is_empty() {
+                        anyhow::bail!("Profile '{}' not found: no profiles are defined in the config file", name);
+                    }
+                    anyhow::bail!("Profile '{}' not found. Available profiles: {}", name, available.join(", "));
+                }
+            },
+            None => ProfileConfig::default(),
+        };
 
-    /// Legacy method for backwards compatibility
-    pub fn from_env() -> Result<Self> {
-        Self::load()
+        // Environment api_key takes precedence over profile and file api_key
+        let api_key = env.api_key.or(profile.api_key).or(file_config.api_key);
+
+        let ca_cert = overrides
+            .ca_cert
+            .clone()
+            .or(env.ca_cert)
+            .or(profile.ca_cert)
+            .or(file_config.ca_cert);
+
+        let default_bank = env.default_bank.or(profile.default_bank).or(file_config.default_bank);
+
+        let (api_url, source) = if let Some(api_url) = overrides.api_url.clone() {
+            (api_url, ConfigSource::CommandLine)
+        } else if let (Some(api_url), Some(name)) = (profile.api_url, &overrides.profile) {
+            (api_url, ConfigSource::Profile(name.clone()))
+        } else if let Some(api_url) = env.api_url {
+            (api_url, ConfigSource::Environment)
+        } else if let Some(api_url) = file_config.api_url {
+            (api_url, ConfigSource::LocalFile)
+        } else {
+            (DEFAULT_API_URL.to_string(), ConfigSource::Default)
+        };
+
+        let mut config = Self::validate_and_create(api_url, api_key, source)?;
+        config.profile = overrides.profile.clone();
+        config.ca_cert = ca_cert;
+        config.default_bank = default_bank;
+        config.history = env.history.or(file_config.history).unwrap_or(true);
+        config.pool_max_idle_per_host = env.pool_max_idle_per_host.or(file_config.pool_max_idle_per_host);
+        config.pool_idle_timeout = env.pool_idle_timeout.or(file_config.pool_idle_timeout);
+        config.output_format = match env.output_format {
+            Some(format) => Some(format),
+            None => file_config
+                .output_format
+                .map(|v| v.parse().context("Invalid output_format in config file"))
+                .transpose()?,
+        };
+        Ok(config)
     }
 
     fn validate_and_create(api_url: String, api_key: Option<String>, source: ConfigSource) -> Result<Self> {
@@ -69,57 +352,36 @@ impl Config {
                 api_url
             );
         }
-        Ok(Config { api_url, api_key, source })
+        Ok(Config { api_url, api_key, source, profile: None, ca_cert: None, default_bank: None, history: true, pool_max_idle_per_host: None, pool_idle_timeout: None, output_format: None })
     }
 
     fn config_dir() -> Option<PathBuf> {
         dirs::home_dir().map(|home| home.join(CONFIG_DIR_NAME))
     }
 
-    fn config_file_path() -> Option<PathBuf> {
+    /// Default config file location (~/.hindsight/config)
+    pub fn config_file_path() -> Option<PathBuf> {
         Self::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
     }
 
-    fn load_from_file() -> Result<Option<(String, Option<String>)>> {
-        let config_path = match Self::config_file_path() {
-            Some(path) => path,
-            None => return Ok(None),
-        };
-
-        if !config_path.exists() {
-            return Ok(None);
+    /// Write `CONFIG_TEMPLATE` to `config_path`, refusing to replace an existing file unless `force`
+    pub fn write_template(config_path: &Path, force: bool) -> Result<()> {
+        if config_path.exists() && !force {
+            anyhow::bail!(
+                "Config file already exists: {} (use --force to overwrite)",
+                config_path.display()
+            );
         }
 
-        let content = fs::read_to_string(&config_path)
-            .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
-
-        let mut api_url: Option<String> = None;
-        let mut api_key: Option<String> = None;
-
-        // Simple TOML parsing for api_url and api_key
-        for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("api_url") {
-                if let Some(value) = line.split('=').nth(1) {
-                    let value = value.trim().trim_matches('"').trim_matches('\'');
-                    if !value.is_empty() {
-                        api_url = Some(value.to_string());
-                    }
-                }
-            } else if line.starts_with("api_key") {
-                if let Some(value) = line.split('=').nth(1) {
-                    let value = value.trim().trim_matches('"').trim_matches('\'');
-                    if !value.is_empty() {
-                        api_key = Some(value.to_string());
-                    }
-                }
+        if let Some(config_dir) = config_path.parent() {
+            if !config_dir.as_os_str().is_empty() && !config_dir.exists() {
+                fs::create_dir_all(config_dir)
+                    .with_context(|| format!("Failed to create config directory: {}", config_dir.display()))?;
             }
         }
 
-        match api_url {
-            Some(url) => Ok(Some((url, api_key))),
-            None => Ok(None),
-        }
+        fs::write(config_path, CONFIG_TEMPLATE)
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))
     }
 
     pub fn save_api_url(api_url: &str) -> Result<PathBuf> {
@@ -127,38 +389,73 @@ impl Config {
     }
 
     pub fn save_config(api_url: &str, api_key: Option<&str>) -> Result<PathBuf> {
-        let config_dir = Self::config_dir()
+        let config_path = Self::config_file_path()
             .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+        Self::save_config_to(&config_path, api_url, api_key)
+    }
 
+    pub fn save_config_to(config_path: &Path, api_url: &str, api_key: Option<&str>) -> Result<PathBuf> {
         // Create config directory if it doesn't exist
-        if !config_dir.exists() {
-            fs::create_dir_all(&config_dir)
-                .with_context(|| format!("Failed to create config directory: {}", config_dir.display()))?;
+        if let Some(config_dir) = config_path.parent() {
+            if !config_dir.as_os_str().is_empty() && !config_dir.exists() {
+                fs::create_dir_all(config_dir)
+                    .with_context(|| format!("Failed to create config directory: {}", config_dir.display()))?;
+            }
         }
 
-        let config_path = config_dir.join(CONFIG_FILE_NAME);
-        let mut content = format!("api_url = \"{}\"\n", api_url);
+        // Only api_url and api_key change; profiles, other settings and comments are kept
+        let existing = match fs::read_to_string(config_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read config file: {}", config_path.display()))
+            }
+        };
+        let mut document = match existing.parse::<toml_edit::DocumentMut>() {
+            Ok(document) => document,
+            // A legacy file only holds api_url and api_key, both written below, so it's replaced
+            Err(_) if FileConfig::from_legacy_lines(&existing).is_some() => toml_edit::DocumentMut::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to parse config file: {}", config_path.display()))
+            }
+        };
+        document["api_url"] = toml_edit::value(api_url);
         if let Some(key) = api_key {
-            content.push_str(&format!("api_key = \"{}\"\n", key));
+            document["api_key"] = toml_edit::value(key);
         }
 
-        fs::write(&config_path, content)
+        fs::write(config_path, document.to_string())
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
 
-        Ok(config_path)
+        Ok(config_path.to_path_buf())
     }
 
     pub fn api_url(&self) -> &str {
         &self.api_url
     }
+
+    /// The bank a command should target: the explicit argument, else `default_bank`,
+    /// with surrounding whitespace removed
+    pub fn bank_id(&self, explicit: Option<String>) -> Result<String> {
+        let bank_id = explicit.or_else(|| self.default_bank.clone()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No bank ID given. Pass one, or set default_bank in the config file (or HINDSIGHT_DEFAULT_BANK)"
+            )
+        })?;
+        let trimmed = bank_id.trim();
+        if trimmed.is_empty() {
+            anyhow::bail!("Bank ID is empty");
+        }
+        Ok(trimmed.to_string())
+    }
 }
 
 /// Prompt user for API URL interactively
 pub fn prompt_api_url(current_url: Option<&str>) -> Result<String> {
     let default = current_url.unwrap_or(DEFAULT_API_URL);
 
-    print!("Enter API URL [{}]: ", default);
-    io::stdout().flush()?;
+    eprint!("Enter API URL [{}]: ", default);
+    io::stderr().flush()?;
 
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
@@ -171,6 +468,19 @@ pub fn prompt_api_url(current_url: Option<&str>) -> Result<String> {
     }
 }
 
+/// Mask an API key for display, keeping only the first and last four characters.
+/// Works on chars rather than bytes so multibyte keys can't cause a slicing panic.
+pub fn mask_api_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() > 8 {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        "****".to_string()
+    }
+}
+
 pub fn generate_doc_id() -> String {
     let now = chrono::Local::now();
     format!("cli_put_{}", now.format("%Y%m%d_%H%M%S"))
@@ -194,6 +504,8 @@ mod tests {
 
     #[test]
     fn test_config_source_display() {
+        assert_eq!(format!("{}", ConfigSource::CommandLine), "command line flag");
+        assert_eq!(format!("{}", ConfigSource::Profile("dev".to_string())), "profile 'dev'");
         assert_eq!(format!("{}", ConfigSource::LocalFile), "config file");
         assert_eq!(format!("{}", ConfigSource::Environment), "environment variable");
         assert_eq!(format!("{}", ConfigSource::Default), "default");
@@ -239,6 +551,332 @@ mod tests {
         assert!(err.contains("Must start with http:// or https://"));
     }
 
+    #[test]
+    fn test_config_template_parses() {
+        let file_config: FileConfig = toml::from_str(CONFIG_TEMPLATE).unwrap();
+        assert_eq!(file_config.api_url.as_deref(), Some(DEFAULT_API_URL));
+        assert!(file_config.api_key.is_none());
+        assert!(file_config.profiles.is_empty());
+    }
+
+    #[test]
+    fn test_write_template_refuses_overwrite() {
+        let path = env::temp_dir().join(format!("hindsight-cli-test-template-{}.toml", std::process::id()));
+        fs::write(&path, "api_url = \"https://keep.example.com\"\n").unwrap();
+
+        let refused = Config::write_template(&path, false);
+        let kept = fs::read_to_string(&path).unwrap();
+        let forced = Config::write_template(&path, true);
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(refused.unwrap_err().to_string().contains("--force"));
+        assert!(kept.contains("keep.example.com"));
+        assert!(forced.is_ok());
+        assert_eq!(written, CONFIG_TEMPLATE);
+    }
+
+    #[test]
+    fn test_save_config_to_keeps_other_settings() {
+        let path = env::temp_dir().join(format!("hindsight-cli-test-save-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "# my settings\napi_url = \"http://old.example.com\"\ndefault_bank = \"notes\"\n\n[profiles.prod]\napi_url = \"https://prod.example.com\"\n",
+        )
+        .unwrap();
+
+        let saved = Config::save_config_to(&path, "https://new.example.com", Some(r#"k"e\y"#));
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        saved.unwrap();
+
+        assert!(written.contains("# my settings"), "{}", written);
+        let file_config: FileConfig = toml::from_str(&written).unwrap();
+        assert_eq!(file_config.api_url.as_deref(), Some("https://new.example.com"));
+        assert_eq!(file_config.api_key.as_deref(), Some(r#"k"e\y"#));
+        assert_eq!(file_config.default_bank.as_deref(), Some("notes"));
+        assert_eq!(file_config.profiles["prod"].api_url.as_deref(), Some("https://prod.example.com"));
+    }
+
+    #[test]
+    fn test_save_config_to_migrates_legacy_file() {
+        let path = env::temp_dir().join(format!("hindsight-cli-test-save-legacy-{}.toml", std::process::id()));
+        fs::write(&path, "api_url = http://old.example.com\n").unwrap();
+        let saved = Config::save_config_to(&path, "http://new.example.com", Some("key"));
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        saved.unwrap();
+        assert_eq!(written, "api_url = \"http://new.example.com\"\napi_key = \"key\"\n");
+    }
+
+    #[test]
+    fn test_save_config_to_new_file() {
+        let path = env::temp_dir().join(format!("hindsight-cli-test-save-new-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Config::save_config_to(&path, "http://localhost:9999", None).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written, "api_url = \"http://localhost:9999\"\n");
+    }
+
+    #[test]
+    fn test_load_with_api_url_override() {
+        let overrides = ConfigOverrides {
+            api_url: Some("https://flag.example.com".to_string()),
+            ..Default::default()
+        };
+        let config = Config::load_with(&overrides).unwrap();
+        assert_eq!(config.api_url, "https://flag.example.com");
+        assert_eq!(config.source, ConfigSource::CommandLine);
+    }
+
+    #[test]
+    fn test_load_with_api_url_override_invalid() {
+        let overrides = ConfigOverrides {
+            api_url: Some("flag.example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(Config::load_with(&overrides).is_err());
+    }
+
+    fn write_temp_config(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("hindsight-cli-test-{}-{}.toml", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_file_config_from_file() {
+        let path = write_temp_config(
+            "valid",
+            "api_url = \"http://file.example.com\"\napi_key = \"file-key\"\n",
+        );
+        let file_config = FileConfig::from_file(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(file_config.api_url, Some("http://file.example.com".to_string()));
+        assert_eq!(file_config.api_key, Some("file-key".to_string()));
+    }
+
+    #[test]
+    fn test_load_with_profile() {
+        let path = write_temp_config(
+            "profile",
+            "api_url = \"http://default.example.com\"\n\n[profiles.dev]\napi_url = \"http://dev.example.com\"\n\n[profiles.prod]\napi_url = \"https://prod.example.com\"\napi_key = \"prod-key\"\n",
+        );
+        let overrides = ConfigOverrides {
+            config_path: Some(path.clone()),
+            profile: Some("dev".to_string()),
+            ..Default::default()
+        };
+        let config = Config::load_with(&overrides);
+        fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+        assert_eq!(config.api_url, "http://dev.example.com");
+        assert_eq!(config.source, ConfigSource::Profile("dev".to_string()));
+        assert_eq!(config.profile, Some("dev".to_string()));
+    }
+
+    #[test]
+    fn test_load_with_unknown_profile() {
+        let path = write_temp_config(
+            "unknown-profile",
+            "[profiles.dev]\napi_url = \"http://dev.example.com\"\n",
+        );
+        let overrides = ConfigOverrides {
+            config_path: Some(path.clone()),
+            profile: Some("staging".to_string()),
+            ..Default::default()
+        };
+        let result = Config::load_with(&overrides);
+        fs::remove_file(&path).unwrap();
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Profile 'staging' not found"));
+        assert!(err.contains("dev"));
+    }
+
+    #[test]
+    fn test_load_with_ca_cert_precedence() {
+        let path = write_temp_config(
+            "ca-cert",
+            "ca_cert = \"/etc/ssl/file-ca.pem\"\n\n[profiles.internal]\nca_cert = \"/etc/ssl/internal-ca.pem\"\n",
+        );
+        let mut overrides = ConfigOverrides {
+            config_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let from_file = Config::load_with(&overrides).map(|c| c.ca_cert);
+
+        overrides.profile = Some("internal".to_string());
+        let from_profile = Config::load_with(&overrides).map(|c| c.ca_cert);
+
+        overrides.ca_cert = Some(PathBuf::from("/tmp/flag-ca.pem"));
+        let from_flag = Config::load_with(&overrides).map(|c| c.ca_cert);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(from_file.unwrap(), Some(PathBuf::from("/etc/ssl/file-ca.pem")));
+        assert_eq!(from_profile.unwrap(), Some(PathBuf::from("/etc/ssl/internal-ca.pem")));
+        assert_eq!(from_flag.unwrap(), Some(PathBuf::from("/tmp/flag-ca.pem")));
+    }
+
+    #[test]
+    fn test_load_with_default_bank_from_profile() {
+        let path = write_temp_config(
+            "default-bank",
+            "default_bank = \"notes\"\n\n[profiles.work]\ndefault_bank = \"work-notes\"\n",
+        );
+        let mut overrides = ConfigOverrides {
+            config_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let from_file = Config::load_layered(&overrides, EnvConfig::default()).map(|c| c.default_bank);
+
+        overrides.profile = Some("work".to_string());
+        let from_profile = Config::load_layered(&overrides, EnvConfig::default()).map(|c| c.default_bank);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(from_file.unwrap().as_deref(), Some("notes"));
+        assert_eq!(from_profile.unwrap().as_deref(), Some("work-notes"));
+    }
+
+    fn env_config(vars: &[(&str, &str)]) -> Result<EnvConfig> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        EnvConfig::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_env_overrides_each_file_setting() {
+        let path = write_temp_config(
+            "env-matrix",
+            "api_url = \"http://file.example.com\"\napi_key = \"file-key\"\nca_cert = \"/etc/ssl/file-ca.pem\"\ndefault_bank = \"file-bank\"\nhistory = true\npool_max_idle_per_host = 8\npool_idle_timeout = 300\noutput_format = \"yaml\"\n",
+        );
+        let overrides = ConfigOverrides { config_path: Some(path.clone()), ..Default::default() };
+        let load = |vars: &[(&str, &str)]| Config::load_layered(&overrides, env_config(vars).unwrap()).unwrap();
+
+        let from_file = load(&[]);
+        let cases: Vec<(&str, &str, Box<dyn Fn(&Config) -> bool>)> = vec![
+            ("HINDSIGHT_API_URL", "http://env.example.com", Box::new(|c: &Config| c.api_url == "http://env.example.com" && c.source == ConfigSource::Environment)),
+            ("HINDSIGHT_API_KEY", "env-key", Box::new(|c: &Config| c.api_key.as_deref() == Some("env-key"))),
+            ("HINDSIGHT_CA_CERT", "/etc/ssl/env-ca.pem", Box::new(|c: &Config| c.ca_cert == Some(PathBuf::from("/etc/ssl/env-ca.pem")))),
+            ("HINDSIGHT_DEFAULT_BANK", "env-bank", Box::new(|c: &Config| c.default_bank.as_deref() == Some("env-bank"))),
+            ("HINDSIGHT_HISTORY", "false", Box::new(|c: &Config| !c.history)),
+            ("HINDSIGHT_POOL_MAX_IDLE_PER_HOST", "2", Box::new(|c: &Config| c.pool_max_idle_per_host == Some(2))),
+            ("HINDSIGHT_POOL_IDLE_TIMEOUT", "30", Box::new(|c: &Config| c.pool_idle_timeout == Some(30))),
+            ("HINDSIGHT_OUTPUT_FORMAT", "JSON", Box::new(|c: &Config| c.output_format == Some(OutputFormat::Json))),
+        ];
+        for (name, value, check) in &cases {
+            assert!(!check(&from_file), "{} already matches without the variable", name);
+            assert!(check(&load(&[(*name, *value)])), "{}={} was not applied", name, value);
+            // An empty variable is the same as an unset one
+            assert!(!check(&load(&[(*name, "")])), "empty {} was applied", name);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_flags_override_env() {
+        let overrides = ConfigOverrides {
+            api_url: Some("http://flag.example.com".to_string()),
+            ca_cert: Some(PathBuf::from("/tmp/flag-ca.pem")),
+            config_path: Some(env::temp_dir().join("hindsight-cli-test-does-not-exist.toml")),
+            ..Default::default()
+        };
+        let env = env_config(&[("HINDSIGHT_API_URL", "http://env.example.com"), ("HINDSIGHT_CA_CERT", "/etc/ssl/env-ca.pem")]);
+        let config = Config::load_layered(&overrides, env.unwrap()).unwrap();
+        assert_eq!(config.api_url, "http://flag.example.com");
+        assert_eq!(config.source, ConfigSource::CommandLine);
+        assert_eq!(config.ca_cert, Some(PathBuf::from("/tmp/flag-ca.pem")));
+    }
+
+    #[test]
+    fn test_env_invalid_values() {
+        let err = env_config(&[("HINDSIGHT_POOL_IDLE_TIMEOUT", "5m")]).unwrap_err().to_string();
+        assert!(err.contains("HINDSIGHT_POOL_IDLE_TIMEOUT"), "{}", err);
+        let err = env_config(&[("HINDSIGHT_HISTORY", "maybe")]).unwrap_err().to_string();
+        assert!(err.contains("HINDSIGHT_HISTORY"), "{}", err);
+        assert_eq!(env_config(&[("HINDSIGHT_HISTORY", "0")]).unwrap().history, Some(false));
+        let err = format!("{:#}", env_config(&[("HINDSIGHT_OUTPUT_FORMAT", "xml")]).unwrap_err());
+        assert!(err.contains("HINDSIGHT_OUTPUT_FORMAT") && err.contains("'xml'"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_with_output_format() {
+        let path = write_temp_config("output-format", "output_format = \"yml\"\n");
+        let overrides = ConfigOverrides { config_path: Some(path.clone()), ..Default::default() };
+        let config = Config::load_layered(&overrides, EnvConfig::default());
+        fs::write(&path, "output_format = \"xml\"\n").unwrap();
+        let invalid = Config::load_layered(&overrides, EnvConfig::default());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(config.unwrap().output_format, Some(OutputFormat::Yaml));
+        let err = format!("{:#}", invalid.unwrap_err());
+        assert!(err.contains("output_format") && err.contains("'xml'"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_with_pool_settings() {
+        let path = write_temp_config("pool", "pool_max_idle_per_host = 8\npool_idle_timeout = 300\n");
+        let overrides = ConfigOverrides {
+            config_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let config = Config::load_with(&overrides);
+        fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+        assert_eq!(config.pool_max_idle_per_host, Some(8));
+        assert_eq!(config.pool_idle_timeout, Some(300));
+    }
+
+    #[test]
+    fn test_bank_id_falls_back_to_default() {
+        let mut config = Config::validate_and_create(
+            "http://localhost:8888".to_string(),
+            None,
+            ConfigSource::Default,
+        )
+        .unwrap();
+        let err = config.bank_id(None).unwrap_err().to_string();
+        assert!(err.contains("default_bank"), "{}", err);
+
+        config.default_bank = Some("notes".to_string());
+        assert_eq!(config.bank_id(None).unwrap(), "notes");
+        assert_eq!(config.bank_id(Some("other".to_string())).unwrap(), "other");
+        assert_eq!(config.bank_id(Some(" other\n".to_string())).unwrap(), "other");
+        assert!(config.bank_id(Some("  ".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_file_config_from_file_empty_values() {
+        let path = write_temp_config("empty", "api_url = \"\"\n");
+        let file_config = FileConfig::from_file(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(file_config.api_url.is_none());
+        assert!(file_config.api_key.is_none());
+    }
+
+    #[test]
+    fn test_file_config_from_file_missing() {
+        let path = env::temp_dir().join("hindsight-cli-test-does-not-exist.toml");
+        assert!(FileConfig::from_file(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_file_config_from_file_legacy_unquoted() {
+        let path = write_temp_config("legacy", "api_url = http://unquoted.example.com\napi_key = legacy-key\n");
+        let file_config = FileConfig::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        let file_config = file_config.unwrap().unwrap();
+        assert_eq!(file_config.api_url.as_deref(), Some("http://unquoted.example.com"));
+        assert_eq!(file_config.api_key.as_deref(), Some("legacy-key"));
+    }
+
+    #[test]
+    fn test_file_config_from_file_malformed() {
+        let path = write_temp_config("malformed", "[profiles\ndefault_bank = notes\n");
+        let result = FileConfig::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(err.contains("Failed to parse config file"));
+    }
+
     #[test]
     fn test_validate_and_create_ftp_url() {
         let config = Config::validate_and_create(
@@ -249,6 +887,20 @@ mod tests {
         assert!(config.is_err());
     }
 
+    #[test]
+    fn test_mask_api_key() {
+        assert_eq!(mask_api_key("sk-1234567890abcd"), "sk-1...abcd");
+        assert_eq!(mask_api_key("short"), "****");
+        assert_eq!(mask_api_key("12345678"), "****");
+    }
+
+    #[test]
+    fn test_mask_api_key_multibyte_boundary() {
+        // 'é' is two bytes and straddles byte offset 4; byte slicing would panic here
+        assert_eq!(mask_api_key("abcé-middle-wxyé"), "abcé...wxyé");
+        assert_eq!(mask_api_key("🔑🔑🔑🔑🔑🔑🔑🔑🔑"), "🔑🔑🔑🔑...🔑🔑🔑🔑");
+    }
+
     #[test]
     fn test_generate_doc_id_format() {
         let doc_id = generate_doc_id();
@@ -320,11 +972,8 @@ mod tests {
 
     #[test]
     fn test_config_api_url_accessor() {
-        let config = Config {
-            api_url: "http://test:8080".to_string(),
-            api_key: None,
-            source: ConfigSource::Default,
-        };
+        let config = Config::validate_and_create("http://test:8080".to_string(), None, ConfigSource::Default).unwrap();
         assert_eq!(config.api_url(), "http://test:8080");
+        assert_eq!(config.output_format, None);
     }
 }
@@ -0,0 +1,120 @@
+//! Multi-turn reflect via `reflect --history-file`
+//!
+//! Each answered query is appended to the file as a `{query, answer}` JSON line, and the
+//! most recent turns are sent back as context with the next query, so follow-up
+//! questions can build on earlier answers.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Default budget for the earlier turns sent with a query
+pub const DEFAULT_HISTORY_TOKENS: usize = 1000;
+
+// Rough size of a token in English text; close enough for a budget
+const CHARS_PER_TOKEN: usize = 4;
+
+/// One question and the answer reflect gave to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    pub query: String,
+    pub answer: String,
+}
+
+/// Read every turn, oldest first. A missing file is an empty conversation, and lines
+/// that don't parse are skipped.
+pub fn load(path: &Path) -> Result<Vec<Turn>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read history file {}", path.display())),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Add a turn to the end of the file, creating it if needed
+pub fn append(path: &Path, turn: &Turn) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open history file {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(turn)?)
+        .with_context(|| format!("Failed to write history file {}", path.display()))
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn render_turn(turn: &Turn) -> String {
+    format!("Q: {}\nA: {}", turn.query.trim(), turn.answer.trim())
+}
+
+/// The most recent turns that fit in `max_tokens`, oldest first, as context for the
+/// next query. Turns are kept whole; `None` when not even the last one fits.
+pub fn render_context(turns: &[Turn], max_tokens: usize) -> Option<String> {
+    let mut used = 0;
+    let mut recent = Vec::new();
+    for turn in turns.iter().rev() {
+        let rendered = render_turn(turn);
+        used += estimate_tokens(&rendered);
+        if used > max_tokens {
+            break;
+        }
+        recent.push(rendered);
+    }
+    if recent.is_empty() {
+        return None;
+    }
+    recent.reverse();
+    Some(format!("Earlier in this conversation:\n\n{}", recent.join("\n\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(query: &str, answer: &str) -> Turn {
+        Turn { query: query.to_string(), answer: answer.to_string() }
+    }
+
+    #[test]
+    fn test_render_context_keeps_recent_turns_within_budget() {
+        let turns = vec![
+            turn("Where does Alice work?", "At a bakery in Berlin."),
+            turn("Since when?", "Since 2021."),
+            turn("Does she like it?", "Yes, she enjoys the early mornings."),
+        ];
+        assert_eq!(
+            render_context(&turns, 1000).unwrap(),
+            "Earlier in this conversation:\n\n\
+             Q: Where does Alice work?\nA: At a bakery in Berlin.\n\n\
+             Q: Since when?\nA: Since 2021.\n\n\
+             Q: Does she like it?\nA: Yes, she enjoys the early mornings."
+        );
+        // The oldest turn is the first to go
+        let two = render_context(&turns, 25).unwrap();
+        assert!(!two.contains("bakery") && two.contains("Since 2021"), "{}", two);
+        assert!(render_context(&turns, 5).is_none());
+        assert!(render_context(&[], 1000).is_none());
+    }
+
+    #[test]
+    fn test_append_load() {
+        let path = std::env::temp_dir().join(format!("hindsight-cli-test-conversation-{}.jsonl", std::process::id()));
+        assert!(load(&path).unwrap().is_empty());
+        append(&path, &turn("Where does Alice work?", "At a bakery.")).unwrap();
+        append(&path, &turn("Since when?", "2021.")).unwrap();
+        assert_eq!(
+            load(&path).unwrap(),
+            vec![turn("Where does Alice work?", "At a bakery."), turn("Since when?", "2021.")]
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,11 +1,190 @@
+use crate::api::{ApiError, NotFoundError};
 use colored::*;
 
+/// Process exit codes, so scripts can branch on the kind of failure:
+///
+/// | Code | Meaning                                              |
+/// |------|------------------------------------------------------|
+/// | 0    | Success                                              |
+/// | 1    | Any other failure                                    |
+/// | 2    | Configuration error (bad URL, unreadable config)     |
+/// | 3    | Network error (connection refused, timeout, DNS)     |
+/// | 4    | API rejected the request (4xx, e.g. 404 or 422)      |
+/// | 5    | `recall --fail-on-empty` found no results            |
+pub mod exit_code {
+    pub const FAILURE: i32 = 1;
+    pub const CONFIG: i32 = 2;
+    pub const NETWORK: i32 = 3;
+    pub const API_CLIENT: i32 = 4;
+    pub const NO_RESULTS: i32 = 5;
+    /// Stopped with Ctrl-C (128 + SIGINT, as shells report it)
+    pub const INTERRUPTED: i32 = 130;
+}
+
+/// Returned by `recall --fail-on-empty` when nothing matched, so scripts can tell
+/// "no results" apart from real failures.
+#[derive(Debug, thiserror::Error)]
+#[error("No results found")]
+pub struct NoResultsError;
+
+/// Returned by `retain-files` when Ctrl-C stopped the upload part way through, after
+/// the summary of what was stored has been printed.
+#[derive(Debug, thiserror::Error)]
+#[error("Interrupted after submitting {submitted} of {total} batches; re-run to upload the rest (retained files are skipped)")]
+pub struct InterruptedError {
+    pub submitted: usize,
+    pub total: usize,
+}
+
 pub fn handle_api_error(err: anyhow::Error, api_url: &str) -> ! {
     eprintln!("{}", format_error_message(&err, api_url));
-    std::process::exit(1);
+    std::process::exit(exit_code_for(&err));
 }
 
+/// Pick the exit code that matches the failure class of an error.
+pub fn exit_code_for(err: &anyhow::Error) -> i32 {
+    if err.downcast_ref::<NoResultsError>().is_some() {
+        return exit_code::NO_RESULTS;
+    }
+    if err.downcast_ref::<InterruptedError>().is_some() {
+        return exit_code::INTERRUPTED;
+    }
+    if err.downcast_ref::<NotFoundError>().is_some() {
+        return exit_code::API_CLIENT;
+    }
+
+    // The server answered, so the status decides; no status means it never got that far
+    if let Some(status) = err.downcast_ref::<ApiError>().and_then(|api_err| api_err.status) {
+        return if (400..500).contains(&status) { exit_code::API_CLIENT } else { exit_code::FAILURE };
+    }
+
+    let err_str = format!("{:#}", err);
+
+    if is_network_error(&err_str) {
+        return exit_code::NETWORK;
+    }
+
+    if is_client_error_status(&err_str) {
+        return exit_code::API_CLIENT;
+    }
+
+    exit_code::FAILURE
+}
+
+fn is_network_error(err_str: &str) -> bool {
+    err_str.contains("Connection refused")
+        || err_str.contains("tcp connect error")
+        || err_str.contains("error sending request")
+        || err_str.contains("timeout")
+        || err_str.contains("Timeout")
+        || err_str.contains("dns")
+        || err_str.contains("DNS")
+        || err_str.contains("failed to lookup")
+}
+
+// The generated client reports response errors as "... status: 422 ..."
+fn is_client_error_status(err_str: &str) -> bool {
+    err_str.match_indices("status: ").any(|(i, m)| {
+        let code = &err_str[i + m.len()..];
+        code.len() >= 3 && code.starts_with('4') && code.as_bytes()[..3].iter().all(u8::is_ascii_digit)
+    })
+}
+
+// Cap on how much of a response body is shown, so an HTML error page can't flood the terminal
+const MAX_RESPONSE_BODY_CHARS: usize = 2000;
+
 fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
+    let message = format_error_summary(err, api_url);
+    match err.downcast_ref::<ApiError>() {
+        Some(api_err) => format!("{}{}", message, format_api_error_details(api_err)),
+        None => message,
+    }
+}
+
+// Helper to show what the server sent back for a failed call
+fn format_api_error_details(api_err: &ApiError) -> String {
+    let Some(body) = &api_err.response_body else {
+        return String::new();
+    };
+    let title = match api_err.status {
+        Some(status) => format!("Server response to {} ({}):", api_err.operation, status),
+        None => format!("Server response to {}:", api_err.operation),
+    };
+    let details: String = format_response_body(body)
+        .lines()
+        .map(|line| format!("\n  {}", line))
+        .collect();
+    format!("\n\n{}{}", title.bright_yellow(), details)
+}
+
+/// Render an error response body for humans. FastAPI validation errors (422) become one
+/// `location: message` line per problem, other JSON is pretty-printed, and anything else
+/// is shown as-is. Long bodies are truncated.
+fn format_response_body(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return truncate_chars(body.trim(), MAX_RESPONSE_BODY_CHARS);
+    };
+
+    match value.get("detail") {
+        Some(serde_json::Value::String(detail)) => return detail.clone(),
+        Some(serde_json::Value::Array(problems)) => {
+            let lines: Vec<String> = problems
+                .iter()
+                .map(|problem| {
+                    let location = problem
+                        .get("loc")
+                        .and_then(|loc| loc.as_array())
+                        .map(|parts| {
+                            parts
+                                .iter()
+                                .map(|part| match part {
+                                    serde_json::Value::String(s) => s.clone(),
+                                    other => other.to_string(),
+                                })
+                                .collect::<Vec<_>>()
+                                .join(".")
+                        })
+                        .unwrap_or_default();
+                    let msg = problem.get("msg").and_then(|m| m.as_str()).unwrap_or("invalid value");
+                    if location.is_empty() {
+                        msg.to_string()
+                    } else {
+                        format!("{}: {}", location, msg)
+                    }
+                })
+                .collect();
+            if !lines.is_empty() {
+                return lines.join("\n");
+            }
+        }
+        _ => {}
+    }
+
+    let pretty = serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string());
+    truncate_chars(&pretty, MAX_RESPONSE_BODY_CHARS)
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}… (truncated)", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn format_error_summary(err: &anyhow::Error, api_url: &str) -> String {
+    if err.downcast_ref::<NoResultsError>().is_some() || err.downcast_ref::<InterruptedError>().is_some() {
+        return format!("{} {}", "✗".bright_red().bold(), err.to_string().bright_red().bold());
+    }
+
+    // Missing resources get a short, specific message instead of the generic 404 hint
+    if let Some(not_found) = err.downcast_ref::<NotFoundError>() {
+        return format!(
+            "{} {}",
+            "✗".bright_red().bold(),
+            not_found.to_string().bright_red().bold()
+        );
+    }
+
     let err_str = err.to_string();
 
     // Connection refused
@@ -74,8 +253,25 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
         );
     }
 
-    // 401/403 Authentication
-    if err_str.contains("401") || err_str.contains("403") {
+    // 401 Unauthorized - missing or rejected API key
+    if err_str.contains("401") {
+        return format!(
+            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  • {}\n  • {}",
+            "✗".bright_red().bold(),
+            "Authentication failed (401)".bright_red().bold(),
+            "API URL:".bright_yellow(),
+            api_url.bright_white(),
+            "Possible causes:".bright_yellow(),
+            "The API requires an API key and none was sent".bright_white(),
+            "The configured API key is invalid or expired".bright_white(),
+            "Try:".bright_green(),
+            "Set the HINDSIGHT_API_KEY environment variable".bright_white(),
+            "Or save it with: hindsight configure --api-key <key>".bright_white()
+        );
+    }
+
+    // 403 Forbidden
+    if err_str.contains("403") {
         return format!(
             "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n\n{}\n  {}",
             "✗".bright_red().bold(),
@@ -137,18 +333,20 @@ fn format_error_message(err: &anyhow::Error, api_url: &str) -> String {
         };
 
         return format!(
-            "{} {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}{}\n\n{}\n  • {}\n  • {}",
+            "{} {}\n\n{}\n  {}\n\n{}\n  {}\n\n{}\n  • {}\n  • {}\n  • {}{}\n\n{}\n  • {}\n  • {}",
             "✗".bright_red().bold(),
             "Invalid API response format".bright_red().bold(),
             "API URL:".bright_yellow(),
             api_url.bright_white(),
+            "Error:".bright_yellow(),
+            err_str.bright_white(),
             "Possible causes:".bright_yellow(),
             "The API returned an unexpected response format".bright_white(),
             "Version mismatch between CLI and API".bright_white(),
             "The API endpoint doesn't exist or returned HTML instead of JSON".bright_white(),
             response_hint,
             "Try:".bright_green(),
-            "Run with --verbose flag to see the full request/response".bright_white(),
+            "Run with -vv to see the full request/response".bright_white(),
             "Ensure you're using a compatible Hindsight API version".bright_white()
         );
     }
@@ -178,8 +376,108 @@ pub fn print_config_help() {
     println!("  {}", "hindsight configure --api-url http://your-api:8888".bright_white());
     println!();
     println!("  {}", "Configuration priority:".bright_yellow());
-    println!("    1. Environment variable (HINDSIGHT_API_URL) - highest priority");
-    println!("    2. Config file (~/.hindsight/config)");
-    println!("    3. Default (http://localhost:8888)");
+    println!("    1. --api-url flag - highest priority, for a single invocation");
+    println!("    2. Environment variable (HINDSIGHT_API_URL)");
+    println!("    3. Config file (~/.hindsight/config, or the path given with --config)");
+    println!("    4. Default (http://localhost:8888)");
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_for_not_found() {
+        let err: anyhow::Error = NotFoundError {
+            kind: "document",
+            id: "doc-1".to_string(),
+        }
+        .into();
+        assert_eq!(exit_code_for(&err), exit_code::API_CLIENT);
+    }
+
+    #[test]
+    fn test_exit_code_for_no_results() {
+        let err: anyhow::Error = NoResultsError.into();
+        assert_eq!(exit_code_for(&err), exit_code::NO_RESULTS);
+    }
+
+    #[test]
+    fn test_exit_code_for_interrupted() {
+        let err: anyhow::Error = InterruptedError { submitted: 3, total: 10 }.into();
+        assert_eq!(exit_code_for(&err), exit_code::INTERRUPTED);
+        assert!(format_error_message(&err, "http://localhost:8888").contains("3 of 10 batches"));
+    }
+
+    #[test]
+    fn test_exit_code_for_network_errors() {
+        let err = anyhow::anyhow!("error sending request for url (http://localhost:8888/v1/default/banks)");
+        assert_eq!(exit_code_for(&err), exit_code::NETWORK);
+        let err = anyhow::anyhow!("operation timed out: Timeout");
+        assert_eq!(exit_code_for(&err), exit_code::NETWORK);
+    }
+
+    #[test]
+    fn test_exit_code_for_client_error_status() {
+        let err = anyhow::anyhow!("Error Response: status: 422 Unprocessable Entity; headers: {{}}");
+        assert_eq!(exit_code_for(&err), exit_code::API_CLIENT);
+    }
+
+    #[test]
+    fn test_exit_code_for_server_error_status() {
+        let err = anyhow::anyhow!("Error Response: status: 500 Internal Server Error");
+        assert_eq!(exit_code_for(&err), exit_code::FAILURE);
+    }
+
+    fn api_error(status: Option<u16>, body: Option<&str>) -> ApiError {
+        ApiError {
+            operation: "retain_memories",
+            status,
+            response_body: body.map(str::to_string),
+            message: "Error Response".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_exit_code_for_api_error_uses_status() {
+        let err: anyhow::Error = api_error(Some(422), None).into();
+        assert_eq!(exit_code_for(&err), exit_code::API_CLIENT);
+        let err: anyhow::Error = api_error(Some(500), None).into();
+        assert_eq!(exit_code_for(&err), exit_code::FAILURE);
+        // Context added by commands doesn't hide the status
+        let err = anyhow::Error::from(api_error(Some(404), None)).context("Recall failed for bank notes");
+        assert_eq!(exit_code_for(&err), exit_code::API_CLIENT);
+    }
+
+    #[test]
+    fn test_format_response_body_validation_errors() {
+        let body = r#"{"detail":[{"loc":["body","items",0,"content"],"msg":"Field required","type":"missing"},{"loc":["query","budget"],"msg":"Input should be 'low', 'mid' or 'high'","type":"enum"}]}"#;
+        assert_eq!(
+            format_response_body(body),
+            "body.items.0.content: Field required\nquery.budget: Input should be 'low', 'mid' or 'high'"
+        );
+    }
+
+    #[test]
+    fn test_format_response_body_other_shapes() {
+        assert_eq!(format_response_body(r#"{"detail":"Bank not found"}"#), "Bank not found");
+        assert_eq!(format_response_body(r#"{"error":"boom"}"#), "{\n  \"error\": \"boom\"\n}");
+        assert_eq!(format_response_body("  <html>Bad Gateway</html>\n"), "<html>Bad Gateway</html>");
+        assert_eq!(truncate_chars("ééééé", 3), "ééé… (truncated)");
+    }
+
+    #[test]
+    fn test_format_api_error_details() {
+        assert_eq!(format_api_error_details(&api_error(Some(422), None)), "");
+        let details = format_api_error_details(&api_error(Some(422), Some(r#"{"detail":"Bad budget"}"#)));
+        assert!(details.contains("retain_memories (422)"), "{}", details);
+        assert!(details.ends_with("\n  Bad budget"), "{}", details);
+    }
+
+    #[test]
+    fn test_exit_code_for_other_errors() {
+        let err = anyhow::anyhow!("Path does not exist: /tmp/missing");
+        assert_eq!(exit_code_for(&err), exit_code::FAILURE);
+    }
+}
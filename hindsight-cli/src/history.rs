@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::debug;
+
+const HISTORY_DIR_NAME: &str = "hindsight";
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+// Cleared by --no-history or `history = false` in the config file
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Allow or forbid recording recall/reflect queries for this invocation
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// One recorded recall or reflect invocation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// RFC 3339 time the command ran
+    pub timestamp: String,
+    /// "recall" or "reflect"
+    pub command: String,
+    /// Bank ID, or the comma-separated list for a multi-bank recall
+    pub bank_id: String,
+    pub query: String,
+    /// Number of results shown; not recorded for reflect
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_count: Option<usize>,
+}
+
+/// History file location (e.g. ~/.local/share/hindsight/history.jsonl on Linux)
+pub fn history_file_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(HISTORY_DIR_NAME).join(HISTORY_FILE_NAME))
+}
+
+/// Append a query to the history file. History is a convenience, so failures are
+/// only logged and never fail the command that ran the query.
+pub fn record(command: &str, bank_id: &str, query: &str, result_count: Option<usize>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(path) = history_file_path() else {
+        return;
+    };
+    let entry = HistoryEntry {
+        timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        command: command.to_string(),
+        bank_id: bank_id.to_string(),
+        query: query.to_string(),
+        result_count,
+    };
+    if let Err(e) = append(&path, &entry) {
+        debug!(path = %path.display(), error = %format!("{:#}", e), "could not record history");
+    }
+}
+
+fn append(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Read every entry, oldest first. A missing file is an empty history, and lines
+/// that don't parse (e.g. from a newer version) are skipped.
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read history file {}", path.display())),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Delete the history file; returns how many entries it held
+pub fn clear(path: &Path) -> Result<usize> {
+    let count = load(path)?.len();
+    match fs::remove_file(path) {
+        Ok(()) => Ok(count),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e).with_context(|| format!("Failed to delete history file {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_load_clear() {
+        let path = std::env::temp_dir()
+            .join(format!("hindsight-cli-test-history-{}", std::process::id()))
+            .join(HISTORY_FILE_NAME);
+        let entry = HistoryEntry {
+            timestamp: "2024-03-01T10:00:00Z".to_string(),
+            command: "recall".to_string(),
+            bank_id: "notes".to_string(),
+            query: "hiking plans".to_string(),
+            result_count: Some(3),
+        };
+
+        assert!(load(&path).unwrap().is_empty());
+        append(&path, &entry).unwrap();
+        // Unreadable lines are skipped rather than failing the whole history
+        fs::write(&path, format!("{}\nnot json\n", serde_json::to_string(&entry).unwrap())).unwrap();
+        append(&path, &entry).unwrap();
+
+        assert_eq!(load(&path).unwrap(), vec![entry.clone(), entry]);
+        assert_eq!(clear(&path).unwrap(), 2);
+        assert!(!path.exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
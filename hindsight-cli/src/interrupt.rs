@@ -0,0 +1,31 @@
+//! Ctrl-C handling for long-running uploads
+//!
+//! By default Ctrl-C kills the process straight away, which is fine for single
+//! requests. `retain-files` calls [`install`] before it starts uploading: the first
+//! Ctrl-C then only asks it to stop, so no new batches are sent, the ones already in
+//! flight finish, and the command reports what was stored before exiting with
+//! [`crate::errors::exit_code::INTERRUPTED`]. A second Ctrl-C exits immediately.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::errors::exit_code;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Catch Ctrl-C for the rest of this invocation instead of exiting on it
+pub fn install() {
+    let result = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(exit_code::INTERRUPTED);
+        }
+        eprintln!("\nInterrupted: finishing the batches already sent (press Ctrl-C again to stop now)");
+    });
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "could not install the Ctrl-C handler");
+    }
+}
+
+/// True once Ctrl-C has been pressed after [`install`]
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -1,23 +1,36 @@
 mod api;
 mod commands;
+mod completion;
 mod config;
+mod conversation;
 mod errors;
+mod history;
+mod interrupt;
+mod manifest;
 mod output;
+mod pager;
+mod recall_cache;
+mod sort;
 mod ui;
 mod utils;
 
 use anyhow::Result;
-use api::ApiClient;
-use clap::{Parser, Subcommand, ValueEnum};
-use config::Config;
+use api::{ApiClient, ClientOptions, Verbosity};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use config::{Config, ConfigOverrides};
 use output::OutputFormat;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing_subscriber::EnvFilter;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum Format {
     Pretty,
     Json,
+    JsonCompact,
+    Ndjson,
     Yaml,
+    Toml,
 }
 
 impl From<Format> for OutputFormat {
@@ -25,7 +38,10 @@ impl From<Format> for OutputFormat {
         match f {
             Format::Pretty => OutputFormat::Pretty,
             Format::Json => OutputFormat::Json,
+            Format::JsonCompact => OutputFormat::JsonCompact,
+            Format::Ndjson => OutputFormat::Ndjson,
             Format::Yaml => OutputFormat::Yaml,
+            Format::Toml => OutputFormat::Toml,
         }
     }
 }
@@ -37,13 +53,89 @@ impl From<Format> for OutputFormat {
 #[command(before_help = get_before_help())]
 #[command(after_help = get_after_help())]
 struct Cli {
-    /// Output format (pretty, json, yaml)
-    #[arg(short = 'o', long, global = true, default_value = "pretty")]
-    output: Format,
+    /// Output format (pretty, json, json-compact, ndjson, yaml, toml; default: output_format from the config file, else pretty)
+    #[arg(short = 'o', long, global = true)]
+    output: Option<Format>,
 
-    /// Show verbose output including full requests and responses
-    #[arg(short = 'v', long, global = true)]
-    verbose: bool,
+    /// More detail on stderr: -v URLs and statuses, -vv request/response bodies, -vvv timings and headers
+    #[arg(short = 'v', long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Log level for diagnostics on stderr (default: warn, or RUST_LOG if set)
+    #[arg(long, global = true, value_name = "LEVEL", value_parser = ["error", "warn", "info", "debug", "trace"])]
+    log_level: Option<String>,
+
+    /// Hide spinners, progress bars and status messages; results and errors still print
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// API URL to connect to (precedence: --api-url > HINDSIGHT_API_URL > config file > default)
+    #[arg(long, global = true)]
+    api_url: Option<String>,
+
+    /// Path to a TOML config file (default: ~/.hindsight/config)
+    #[arg(long, global = true, value_name = "PATH", env = "HINDSIGHT_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Named profile from the config file to use (e.g. [profiles.dev])
+    #[arg(long, global = true, env = "HINDSIGHT_PROFILE")]
+    profile: Option<String>,
+
+    /// Retries for transient failures (429, 502, 503, 504, connection errors)
+    #[arg(long, global = true, default_value = "3", env = "HINDSIGHT_RETRIES")]
+    retries: u32,
+
+    /// Per-request timeout in seconds (0 disables the timeout)
+    #[arg(long, global = true, value_name = "SECONDS", default_value = "120", env = "HINDSIGHT_TIMEOUT")]
+    timeout: u64,
+
+    /// X-Request-Id sent with every request (default: a new random UUID per request, shown with -v)
+    #[arg(long, global = true, value_name = "ID")]
+    request_id: Option<String>,
+
+    /// Skip TLS certificate verification (for self-signed servers; never use in production)
+    #[arg(short = 'k', long, global = true)]
+    insecure: bool,
+
+    /// Trust an extra PEM root certificate, e.g. an internal CA (config key: ca_cert)
+    #[arg(long, global = true, value_name = "PATH")]
+    cacert: Option<PathBuf>,
+
+    /// Extra header sent with every request, as "Name: Value" (repeatable)
+    #[arg(long = "header", global = true, value_name = "NAME: VALUE", value_parser = ClientOptions::parse_header)]
+    headers: Vec<(String, String)>,
+
+    /// Send requests through this HTTP or SOCKS5 proxy (default: HTTPS_PROXY/HTTP_PROXY, honouring NO_PROXY)
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// Show fact dates as returned by the API instead of "3 days ago" for recent ones
+    #[arg(long, global = true)]
+    absolute_dates: bool,
+
+    /// Widest a table may get ("auto" for the terminal width); longer bank IDs are cut with "…"
+    #[arg(long, global = true, value_name = "COLUMNS", value_parser = ui::TableWidth::parse)]
+    table_width: Option<ui::TableWidth>,
+
+    /// Warn when a bank ID isn't one the server knows, suggesting the closest match (one extra request)
+    #[arg(long, global = true)]
+    check_bank: bool,
+
+    /// Write results to this file instead of stdout (pretty output is written without colors)
+    #[arg(long, global = true, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Print long pretty output straight to the terminal instead of through $PAGER (default: less -FRX)
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Don't record recall/reflect queries in the local history (config key: history = false)
+    #[arg(long, global = true)]
+    no_history: bool,
+
+    /// Report how long the command took (pretty: a final line; json/yaml: metadata.elapsed_ms)
+    #[arg(long, global = true)]
+    timing: bool,
 
     #[command(subcommand)]
     command: Commands,
@@ -56,7 +148,7 @@ fn get_after_help() -> String {
         None => ("http://localhost:8888", "default".to_string()),
     };
     format!(
-        "Current API URL: {} (from {})\n\nRun 'hindsight configure' to change the API URL.",
+        "Current API URL: {} (from {})\n\nRun 'hindsight configure' to change the API URL.\n\nExit codes: 0 success, 1 failure, 2 configuration error, 3 network error, 4 API rejected the request (4xx), 5 recall --fail-on-empty found no results, 130 retain-files stopped with Ctrl-C",
         api_url, source
     )
 }
@@ -71,7 +163,7 @@ enum Commands {
     #[command(subcommand)]
     Bank(BankCommands),
 
-    /// Manage memories (recall, reflect, retain, delete)
+    /// Manage memories (recall, reflect, retain, import, export, delete)
     #[command(subcommand)]
     Memory(MemoryCommands),
 
@@ -83,7 +175,7 @@ enum Commands {
     #[command(subcommand)]
     Entity(EntityCommands),
 
-    /// Manage async operations (list, cancel)
+    /// Manage async operations (list, status, cancel)
     #[command(subcommand)]
     Operation(OperationCommands),
 
@@ -94,33 +186,71 @@ enum Commands {
     /// Launch the web-based control plane UI
     Ui,
 
+    /// Check that the API server is reachable and healthy
+    Health,
+
     /// Configure the CLI (API URL, API key, etc.)
     #[command(after_help = "Configuration priority:\n  1. Environment variables (HINDSIGHT_API_URL, HINDSIGHT_API_KEY) - highest priority\n  2. Config file (~/.hindsight/config)\n  3. Default (http://localhost:8888)")]
     Configure {
-        /// API URL to connect to (interactive prompt if not provided)
-        #[arg(long)]
-        api_url: Option<String>,
         /// API key for authentication (sent as Bearer token)
         #[arg(long)]
         api_key: Option<String>,
     },
+
+    /// Create or inspect the config file
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Browse or clear the local history of recall and reflect queries
+    #[command(subcommand)]
+    History(HistoryCommands),
+
+    /// Manage recall results saved with `memory recall --cache`
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
+    /// Print a shell completion script (bash and zsh also complete bank IDs from the server)
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
+
+    /// Print bank IDs one per line, for completion scripts
+    #[command(name = "__complete-banks", hide = true)]
+    CompleteBanks,
 }
 
 #[derive(Subcommand)]
 enum BankCommands {
     /// List all banks
-    List,
+    List {
+        /// Also show each bank's name, memory and document counts and last update (one stats call per bank)
+        #[arg(long)]
+        detailed: bool,
+
+        /// Only banks whose ID or name contains this text (case-insensitive)
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+
+        /// Only banks whose ID starts with this text (case-insensitive)
+        #[arg(long, value_name = "TEXT")]
+        prefix: Option<String>,
+    },
 
     /// Get bank disposition and background
     Disposition {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
     },
 
     /// Get memory statistics for a bank
     Stats {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
+
+        /// Also report the earliest and latest memory dates (scans every memory)
+        #[arg(long)]
+        date_range: bool,
     },
 
     /// Set bank name
@@ -159,23 +289,33 @@ enum BankCommands {
 #[derive(Subcommand)]
 enum MemoryCommands {
     /// Recall memories using semantic search
+    #[command(allow_missing_positional = true)]
     Recall {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID, or several comma-separated bank IDs to search them all (default: default_bank from the config file)
+        bank_id: Option<String>,
 
-        /// Search query
-        query: String,
+        /// Search query (use "-" to read from stdin)
+        #[arg(required_unless_present = "queries_file")]
+        query: Option<String>,
 
-        /// Fact types to search (world, experience, opinion)
-        #[arg(short = 't', long, value_delimiter = ',', default_values = &["world", "experience", "opinion"])]
-        fact_type: Vec<String>,
+        /// Run every query in this file (one per line) instead of a single query
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["trace", "include_chunks", "group_by_type", "summary_only", "keep_going", "copy", "fail_on_empty", "cache", "offline"])]
+        queries_file: Option<PathBuf>,
+
+        /// Number of queries from --queries-file run in parallel
+        #[arg(long, default_value = "4", requires = "queries_file")]
+        concurrency: usize,
+
+        /// Fact types to search, comma-separated; "all" means every type
+        #[arg(short = 't', long, value_enum, value_delimiter = ',', default_value = "all")]
+        fact_type: Vec<commands::memory::FactType>,
 
         /// Thinking budget (low, mid, high)
-        #[arg(short = 'b', long, default_value = "mid")]
+        #[arg(short = 'b', long, default_value = "mid", ignore_case = true, value_parser = ["low", "mid", "high"])]
         budget: String,
 
         /// Maximum tokens for results
-        #[arg(long, default_value = "4096")]
+        #[arg(long, default_value = "4096", value_parser = clap::value_parser!(i64).range(1..=commands::memory::MAX_TOKENS_LIMIT))]
         max_tokens: i64,
 
         /// Show trace information
@@ -187,20 +327,89 @@ enum MemoryCommands {
         include_chunks: bool,
 
         /// Maximum tokens for chunks (only used with --include-chunks)
-        #[arg(long, default_value = "8192")]
+        #[arg(long, default_value = "8192", value_parser = clap::value_parser!(i64).range(1..=commands::memory::MAX_TOKENS_LIMIT))]
         chunk_max_tokens: i64,
+
+        /// Only show the top N results
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+
+        /// Order results client-side before display
+        #[arg(long, value_enum, default_value = "none")]
+        sort: sort::SortBy,
+
+        /// With --sort date, order results with equal dates by ID, then text, so output is identical across runs
+        #[arg(long)]
+        stable_order: bool,
+
+        /// Only keep results whose context contains this text (case-insensitive)
+        #[arg(long, value_name = "SUBSTRING")]
+        context: Option<String>,
+
+        /// Only keep results that occurred on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Only keep results that occurred on or before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_name = "DATE")]
+        until: Option<String>,
+
+        /// Highlight query terms in pretty output (default)
+        #[arg(long, overrides_with = "no_highlight")]
+        highlight: bool,
+
+        /// Don't highlight query terms
+        #[arg(long, overrides_with = "highlight")]
+        no_highlight: bool,
+
+        /// Group pretty output under a heading per fact type (json/yaml stay flat)
+        #[arg(long)]
+        group_by_type: bool,
+
+        /// Print only how many results matched, in total and per fact type (and per bank)
+        #[arg(long, conflicts_with_all = ["trace", "include_chunks", "group_by_type", "copy"])]
+        summary_only: bool,
+
+        /// Drop results that repeat an earlier result's ID or text, keeping the best-ranked copy
+        #[arg(long)]
+        dedup: bool,
+
+        /// With several banks, still show results from the banks that answered if others fail
+        #[arg(long)]
+        keep_going: bool,
+
+        /// Also copy the results, as JSON, to the clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Exit with code 5 when no results are left after filtering
+        #[arg(long)]
+        fail_on_empty: bool,
+
+        /// Save the results locally so they can be viewed again with --offline
+        #[arg(long)]
+        cache: bool,
+
+        /// Show saved results for this bank, query and fact types instead of asking the server
+        #[arg(long, conflicts_with = "cache")]
+        offline: bool,
+
+        /// With --offline, ignore saved results older than this many seconds
+        #[arg(long, value_name = "SECONDS", default_value_t = recall_cache::DEFAULT_TTL_SECS, requires = "offline")]
+        cache_ttl: u64,
     },
 
     /// Generate answers using bank identity (reflect/reasoning)
+    #[command(allow_missing_positional = true)]
     Reflect {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
 
-        /// Query to reflect on
+        /// Query to reflect on (use "-" to read from stdin)
         query: String,
 
         /// Thinking budget (low, mid, high)
-        #[arg(short = 'b', long, default_value = "mid")]
+        #[arg(short = 'b', long, default_value = "mid", ignore_case = true, value_parser = ["low", "mid", "high"])]
         budget: String,
 
         /// Additional context
@@ -208,29 +417,71 @@ enum MemoryCommands {
         context: Option<String>,
 
         /// Maximum tokens for the response (server default: 4096)
-        #[arg(short = 'm', long)]
+        #[arg(short = 'm', long, value_parser = clap::value_parser!(i64).range(1..=commands::memory::MAX_TOKENS_LIMIT))]
         max_tokens: Option<i64>,
 
         /// Path to JSON schema file for structured output
         #[arg(short = 's', long)]
         schema: Option<PathBuf>,
+
+        /// Render the answer as Markdown (default when stdout is a terminal)
+        #[arg(long, overrides_with = "no_markdown")]
+        markdown: bool,
+
+        /// Print the answer as plain text
+        #[arg(long, overrides_with = "markdown")]
+        no_markdown: bool,
+
+        /// Print only this text, with {text}, {bank}, {query} and {mental_model_count} filled in
+        #[arg(long, value_name = "TEMPLATE")]
+        template: Option<String>,
+
+        /// List the facts and mental models the answer was based on after it (pretty output)
+        #[arg(long)]
+        explain: bool,
+
+        /// Conversation file: earlier answers are sent as context and this one is appended
+        #[arg(long, value_name = "PATH")]
+        history_file: Option<PathBuf>,
+
+        /// Most tokens of earlier turns to send from --history-file (oldest are dropped first)
+        #[arg(long, value_name = "TOKENS", default_value_t = conversation::DEFAULT_HISTORY_TOKENS, requires = "history_file")]
+        history_tokens: usize,
+
+        /// Also copy the answer text to the clipboard
+        #[arg(long)]
+        copy: bool,
     },
 
-    /// Store (retain) a single memory
+    /// Store (retain) one or more memories
+    #[command(allow_missing_positional = true)]
     Retain {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
 
-        /// Memory content
-        content: String,
+        /// Memory content; repeat to store several memories in one request (use "-" to read from stdin)
+        #[arg(required_unless_present_any = ["file", "from_clipboard"])]
+        content: Vec<String>,
+
+        /// Read the memory content from this file instead
+        #[arg(short = 'f', long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// Use the text on the system clipboard as the memory content
+        #[arg(long, conflicts_with = "file")]
+        from_clipboard: bool,
 
         /// Document ID (auto-generated if not provided)
         #[arg(short = 'd', long)]
         doc_id: Option<String>,
 
-        /// Context for the memory
+        /// Context for the memory; give once for all contents or once per content, in order
         #[arg(short = 'c', long)]
-        context: Option<String>,
+        context: Vec<String>,
+
+        /// Split content longer than this many characters into several memories of the same document
+        #[arg(long, value_name = "CHARS")]
+        chunk_size: Option<usize>,
 
         /// Queue for background processing
         #[arg(long)]
@@ -238,24 +489,69 @@ enum MemoryCommands {
     },
 
     /// Bulk import memories from files (retain)
+    #[command(allow_missing_positional = true)]
     RetainFiles {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
+
+        /// Store each file in the bank named by its directory this many levels below PATH (1 = top-level folders)
+        #[arg(long, value_name = "DEPTH", conflicts_with = "bank_id", value_parser = clap::value_parser!(u16).range(1..))]
+        bank_from_path: Option<u16>,
 
-        /// Path to file or directory
+        /// Path to a file or directory, or a quoted glob pattern such as "notes/**/*.md"
         path: PathBuf,
 
         /// Search directories recursively
         #[arg(short = 'r', long, default_value = "true")]
         recursive: bool,
 
+        /// File extensions to import, comma-separated (case-insensitive)
+        #[arg(long = "ext", value_delimiter = ',', default_values = commands::memory::DEFAULT_TEXT_EXTENSIONS)]
+        extensions: Vec<String>,
+
         /// Context for all memories
         #[arg(short = 'c', long)]
         context: Option<String>,
 
+        /// Use each file's parent directory name as its context
+        #[arg(long, conflicts_with = "context")]
+        context_from_path: bool,
+
+        /// Number of files (or file parts, with --chunk-size) sent per retain request
+        #[arg(long, default_value = "50")]
+        batch_size: usize,
+
+        /// Number of batches uploaded in parallel
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+
+        /// Split files longer than this many characters into several memories of the same document
+        #[arg(long, value_name = "CHARS")]
+        chunk_size: Option<usize>,
+
         /// Queue for background processing
         #[arg(long)]
         r#async: bool,
+
+        /// Retain every file, even those unchanged since the last run
+        #[arg(long)]
+        force: bool,
+
+        /// Skip files larger than this, e.g. 500KB or 10MB
+        #[arg(long, value_name = "SIZE", default_value = "5MB", value_parser = commands::memory::parse_file_size)]
+        max_file_size: u64,
+
+        /// Keep submitting batches after one fails (default: stop at the first failure)
+        #[arg(long)]
+        keep_going: bool,
+
+        /// With --keep-going, stop the import once this many batches fail in a row
+        #[arg(long, value_name = "N", default_value = "5")]
+        max_consecutive_failures: usize,
+
+        /// Skip the confirmation prompt for large imports
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 
     /// Delete a memory unit
@@ -267,6 +563,42 @@ enum MemoryCommands {
         unit_id: String,
     },
 
+    /// Import memories from a JSON file (an array of {content, context, timestamp} objects, or a `memory export` file)
+    #[command(allow_missing_positional = true)]
+    Import {
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
+
+        /// Path to the JSON file
+        path: PathBuf,
+
+        /// Queue for background processing
+        #[arg(long)]
+        r#async: bool,
+    },
+
+    /// Export all memories for a bank (JSON unless -o selects another format)
+    Export {
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
+
+        /// Write each document's original text to its own file in this directory instead
+        #[arg(long, value_name = "DIR", group = "export_target")]
+        out_dir: Option<PathBuf>,
+
+        /// File type for --out-dir: md (with context/date front matter) or txt
+        #[arg(long, default_value = "md", value_parser = ["md", "txt"], requires = "out_dir")]
+        ext: String,
+
+        /// Stream memories to this file as NDJSON while pages arrive, for banks too large to buffer
+        #[arg(long, value_name = "PATH", group = "export_target")]
+        jsonl_file: Option<PathBuf>,
+
+        /// Overwrite existing files in --out-dir, or an existing --jsonl-file
+        #[arg(long, requires = "export_target")]
+        force: bool,
+    },
+
     /// Clear all memories for a bank
     Clear {
         /// Bank ID
@@ -286,8 +618,8 @@ enum MemoryCommands {
 enum DocumentCommands {
     /// List documents for a bank
     List {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
 
         /// Search query to filter documents
         #[arg(short = 'q', long)]
@@ -311,13 +643,26 @@ enum DocumentCommands {
         document_id: String,
     },
 
-    /// Delete a document and all its memory units
+    /// Delete a document and all its memory units, or every document matching --prefix/--glob
+    #[command(group(clap::ArgGroup::new("target").required(true).args(["document_id", "prefix", "glob"])))]
     Delete {
         /// Bank ID
         bank_id: String,
 
         /// Document ID
-        document_id: String,
+        document_id: Option<String>,
+
+        /// Delete every document whose ID starts with this
+        #[arg(long, conflicts_with_all = ["document_id", "glob"])]
+        prefix: Option<String>,
+
+        /// Delete every document whose ID matches this pattern (* and ? wildcards)
+        #[arg(long, conflicts_with = "document_id")]
+        glob: Option<String>,
+
+        /// Skip the confirmation prompt for --prefix/--glob
+        #[arg(short = 'y', long)]
+        yes: bool,
     },
 }
 
@@ -325,8 +670,8 @@ enum DocumentCommands {
 enum EntityCommands {
     /// List entities for a bank
     List {
-        /// Bank ID
-        bank_id: String,
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
 
         /// Maximum number of results
         #[arg(short = 'l', long, default_value = "100")]
@@ -356,8 +701,21 @@ enum EntityCommands {
 enum OperationCommands {
     /// List async operations for a bank
     List {
+        /// Bank ID (default: default_bank from the config file)
+        bank_id: Option<String>,
+    },
+
+    /// Show the status of an async operation
+    Status {
         /// Bank ID
         bank_id: String,
+
+        /// Operation ID
+        operation_id: String,
+
+        /// Poll until the operation is no longer pending
+        #[arg(short = 'w', long)]
+        wait: bool,
     },
 
     /// Cancel a pending async operation
@@ -370,127 +728,362 @@ enum OperationCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Write a commented starter config file (~/.hindsight/config, or --config)
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show the effective configuration and where each value came from (API key masked)
+    Show,
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Delete every saved recall result
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum HistoryCommands {
+    /// Show recent queries, newest first
+    List {
+        /// Maximum number of entries
+        #[arg(short = 'l', long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Delete the history file
+    Clear {
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
 fn main() {
-    if let Err(_) = run() {
-        std::process::exit(1);
+    if let Err(e) = run() {
+        ui::print_error(&format!("{:#}", e));
+        std::process::exit(errors::exit_code_for(&e));
     }
 }
 
+/// Send tracing output to stderr so it never mixes with command output.
+/// --log-level wins over -v (debug, or trace from -vv), which wins over RUST_LOG; the default is warn.
+fn init_logging(log_level: Option<&str>, verbose: Verbosity) {
+    let filter = match (log_level, verbose.log_filter()) {
+        (Some(level), _) => EnvFilter::new(format!("hindsight={}", level)),
+        (None, Some(filter)) => EnvFilter::new(filter),
+        (None, None) => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
 fn run() -> Result<()> {
     let cli = Cli::parse();
 
-    let output_format: OutputFormat = cli.output.into();
-    let verbose = cli.verbose;
+    // Commands that run before the config is loaded can't use its output_format
+    let format_flag: Option<OutputFormat> = cli.output.map(Into::into);
+    let mut output_format = output::resolve_format(format_flag, None);
+    let verbose = Verbosity(cli.verbose);
+    ui::set_quiet(cli.quiet);
+    ui::set_absolute_dates(cli.absolute_dates);
+    ui::set_table_width(cli.table_width);
+    pager::set_enabled(!cli.no_pager);
+    init_logging(cli.log_level.as_deref(), verbose);
+
+    if let Some(path) = cli.out.clone() {
+        output::set_out_path(path)?;
+    }
+    output::route_stdout(output_format)?;
 
     // Handle configure command before loading full config (it doesn't need API client)
-    if let Commands::Configure { api_url, api_key } = cli.command {
-        return handle_configure(api_url, api_key, output_format);
+    // (the global --api-url flag doubles as the value to save; prompt if not provided)
+    if let Commands::Configure { api_key } = cli.command {
+        return handle_configure(cli.api_url, api_key, cli.config, output_format);
+    }
+
+    // Completion scripts are generated from the CLI definition alone
+    if let Commands::Completions { shell } = cli.command {
+        print_completions(shell);
+        return Ok(());
     }
 
+    let overrides = ConfigOverrides {
+        api_url: cli.api_url,
+        config_path: cli.config,
+        profile: cli.profile,
+        ca_cert: cli.cacert,
+    };
+
     // Handle ui command - needs config but not API client
     if let Commands::Ui = cli.command {
-        return handle_ui(output_format);
+        return handle_ui(&overrides, output_format);
+    }
+
+    // Config commands work on the config file itself, so they run before it's loaded
+    if let Commands::Config(config_cmd) = cli.command {
+        return match config_cmd {
+            ConfigCommands::Init { force } => commands::config::init(&overrides, force, output_format),
+            ConfigCommands::Show => commands::config::show(&overrides, output_format),
+        };
+    }
+
+    // History is a local file, so it needs neither the config nor an API client
+    if let Commands::History(history_cmd) = cli.command {
+        return match history_cmd {
+            HistoryCommands::List { limit } => commands::history::list(limit, output_format),
+            HistoryCommands::Clear { yes } => commands::history::clear(yes, output_format),
+        };
+    }
+
+    // The recall cache is local too
+    if let Commands::Cache(CacheCommands::Clear) = cli.command {
+        return commands::cache::clear(output_format);
     }
 
     // Load configuration
-    let config = Config::from_env().unwrap_or_else(|e| {
-        ui::print_error(&format!("Configuration error: {}", e));
+    let config = Config::load_with(&overrides).unwrap_or_else(|e| {
+        ui::print_error(&format!("Configuration error: {:#}", e));
         errors::print_config_help();
-        std::process::exit(1);
+        std::process::exit(errors::exit_code::CONFIG);
     });
+    history::set_enabled(config.history && !cli.no_history);
+    output_format = output::resolve_format(format_flag, config.output_format);
+    output::route_stdout(output_format)?;
 
     let api_url = config.api_url().to_string();
     let api_key = config.api_key.clone();
 
     // Create API client
-    let client = ApiClient::new(api_url.clone(), api_key).unwrap_or_else(|e| {
+    let client_options = ClientOptions {
+        max_retries: cli.retries,
+        timeout: ClientOptions::timeout_from_secs(cli.timeout),
+        request_id: cli.request_id,
+        insecure: cli.insecure,
+        ca_cert: config.ca_cert.clone(),
+        proxy: cli.proxy,
+        headers: cli.headers,
+        pool_max_idle_per_host: config.pool_max_idle_per_host,
+        pool_idle_timeout: config.pool_idle_timeout.map(Duration::from_secs),
+    };
+    if cli.insecure {
+        let warning = "TLS certificate verification is disabled (--insecure)";
+        if output_format == OutputFormat::Pretty {
+            ui::print_warning(warning);
+        } else {
+            // Keep stdout clean for structured output
+            eprintln!("warning: {}", warning);
+        }
+    }
+    let client = ApiClient::with_options(api_url.clone(), api_key, client_options).unwrap_or_else(|e| {
         errors::handle_api_error(e, &api_url);
     });
 
-    // Execute command and handle errors
+    // Runs on every <TAB>, so it must stay quiet: no spinner, and errors print nothing
+    if let Commands::CompleteBanks = cli.command {
+        complete_banks(&client, &api_url);
+        return Ok(());
+    }
+
+    // Execute command and handle errors, timing it end to end for --timing
+    let started = Instant::now();
+    if cli.timing {
+        output::set_timing_start(started);
+    }
+    let is_explore = matches!(cli.command, Commands::Explore);
+    // Every bank argument goes through here, so IDs are trimmed and --check-bank applies
+    let check_bank = cli.check_bank;
+    let resolve_bank = |explicit: Option<String>| -> Result<String> {
+        let bank_id = config.bank_id(explicit)?;
+        if check_bank {
+            commands::bank::check_known(&client, &bank_id, verbose, output_format)?;
+        }
+        Ok(bank_id)
+    };
     let result: Result<()> = match cli.command {
         Commands::Configure { .. } => unreachable!(), // Handled above
         Commands::Ui => unreachable!(), // Handled above
+        Commands::Config(_) => unreachable!(), // Handled above
+        Commands::History(_) => unreachable!(), // Handled above
+        Commands::Cache(_) => unreachable!(), // Handled above
+        Commands::Completions { .. } => unreachable!(), // Handled above
+        Commands::CompleteBanks => unreachable!(), // Handled above
         Commands::Explore => commands::explore::run(&client),
+        Commands::Health => commands::health::check(&client, &api_url, verbose, output_format),
         Commands::Bank(bank_cmd) => match bank_cmd {
-            BankCommands::List => commands::bank::list(&client, verbose, output_format),
-            BankCommands::Disposition { bank_id } => commands::bank::disposition(&client, &bank_id, verbose, output_format),
-            BankCommands::Stats { bank_id } => commands::bank::stats(&client, &bank_id, verbose, output_format),
-            BankCommands::Name { bank_id, name } => commands::bank::update_name(&client, &bank_id, &name, verbose, output_format),
+            BankCommands::List { detailed, filter, prefix } => {
+                let filter = commands::bank::BankFilter { contains: filter, prefix };
+                commands::bank::list(&client, detailed, &filter, verbose, output_format)
+            }
+            BankCommands::Disposition { bank_id } => commands::bank::disposition(&client, &resolve_bank(bank_id)?, verbose, output_format),
+            BankCommands::Stats { bank_id, date_range } => commands::bank::stats(&client, &resolve_bank(bank_id)?, date_range, verbose, output_format),
+            BankCommands::Name { bank_id, name } => commands::bank::update_name(&client, &resolve_bank(Some(bank_id))?, &name, verbose, output_format),
             BankCommands::Background { bank_id, content, no_update_disposition } => {
-                commands::bank::update_background(&client, &bank_id, &content, no_update_disposition, verbose, output_format)
+                commands::bank::update_background(&client, &resolve_bank(Some(bank_id))?, &content, no_update_disposition, verbose, output_format)
             }
             BankCommands::Delete { bank_id, yes } => {
-                commands::bank::delete(&client, &bank_id, yes, verbose, output_format)
+                commands::bank::delete(&client, &resolve_bank(Some(bank_id))?, yes, verbose, output_format)
             }
         },
 
         Commands::Memory(memory_cmd) => match memory_cmd {
-            MemoryCommands::Recall { bank_id, query, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens } => {
-                commands::memory::recall(&client, &bank_id, query, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens, verbose, output_format)
+            MemoryCommands::Recall { mut bank_id, mut query, queries_file: Some(queries_file), concurrency, fact_type, budget, max_tokens, limit, sort, stable_order, context, since, until, highlight: _, no_highlight, dedup, .. } => {
+                // clap hands a lone positional to the last positional, so with
+                // --queries-file it is really the bank ID
+                if bank_id.is_none() {
+                    bank_id = query.take();
+                }
+                commands::memory::recall_queries_file(&client, &resolve_bank(bank_id)?, query, &queries_file, fact_type, budget, max_tokens, limit, sort, stable_order, context, since, until, !no_highlight, dedup, concurrency, verbose, output_format)
             }
-            MemoryCommands::Reflect { bank_id, query, budget, context, max_tokens, schema } => {
-                commands::memory::reflect(&client, &bank_id, query, budget, context, max_tokens, schema, verbose, output_format)
+            MemoryCommands::Recall { bank_id, query, queries_file: None, concurrency: _, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens, limit, sort, stable_order, context, since, until, highlight: _, no_highlight, group_by_type, summary_only, dedup, keep_going, copy, fail_on_empty, cache, offline, cache_ttl } => {
+                let query = query.expect("clap requires a query without --queries-file");
+                let cache_mode = if offline {
+                    commands::memory::CacheMode::Offline(Duration::from_secs(cache_ttl))
+                } else if cache {
+                    commands::memory::CacheMode::Store
+                } else {
+                    commands::memory::CacheMode::Off
+                };
+                commands::memory::recall(&client, &resolve_bank(bank_id)?, query, fact_type, budget, max_tokens, trace, include_chunks, chunk_max_tokens, limit, sort, stable_order, context, since, until, !no_highlight, group_by_type, summary_only, dedup, keep_going, copy, fail_on_empty, cache_mode, verbose, output_format)
             }
-            MemoryCommands::Retain { bank_id, content, doc_id, context, r#async } => {
-                commands::memory::retain(&client, &bank_id, content, doc_id, context, r#async, verbose, output_format)
+            MemoryCommands::Reflect { bank_id, query, budget, context, max_tokens, schema, markdown, no_markdown, template, explain, history_file, history_tokens, copy } => {
+                let markdown = if markdown { Some(true) } else if no_markdown { Some(false) } else { None };
+                commands::memory::reflect(&client, &resolve_bank(bank_id)?, query, budget, context, max_tokens, schema, markdown, template, explain, history_file, history_tokens, copy, verbose, output_format)
             }
-            MemoryCommands::RetainFiles { bank_id, path, recursive, context, r#async } => {
-                commands::memory::retain_files(&client, &bank_id, path, recursive, context, r#async, verbose, output_format)
+            MemoryCommands::Retain { mut bank_id, mut content, file, from_clipboard, doc_id, context, chunk_size, r#async } => {
+                // clap hands a lone positional followed by a flag to the last positional,
+                // so with --file or --from-clipboard it is really the bank ID
+                if (file.is_some() || from_clipboard) && bank_id.is_none() && content.len() == 1 {
+                    bank_id = content.pop();
+                }
+                commands::memory::retain(&client, &resolve_bank(bank_id)?, content, file, from_clipboard, doc_id, context, chunk_size, r#async, verbose, output_format)
+            }
+            MemoryCommands::RetainFiles { bank_id, bank_from_path, path, recursive, extensions, context, context_from_path, batch_size, concurrency, chunk_size, r#async, force, max_file_size, keep_going, max_consecutive_failures, yes } => {
+                let banks = match bank_from_path {
+                    Some(depth) => commands::memory::FileBanks::FromPath(depth.into()),
+                    None => commands::memory::FileBanks::Fixed(resolve_bank(bank_id)?),
+                };
+                commands::memory::retain_files(&client, banks, path, recursive, extensions, context, context_from_path, batch_size, concurrency, chunk_size, r#async, force, max_file_size, keep_going, max_consecutive_failures, yes, verbose, output_format)
             }
             MemoryCommands::Delete { bank_id, unit_id } => {
-                commands::memory::delete(&client, &bank_id, &unit_id, verbose, output_format)
+                commands::memory::delete(&client, &resolve_bank(Some(bank_id))?, &unit_id, verbose, output_format)
+            }
+            MemoryCommands::Import { bank_id, path, r#async } => {
+                commands::memory::import(&client, &resolve_bank(bank_id)?, path, r#async, verbose, output_format)
+            }
+            MemoryCommands::Export { bank_id, out_dir, ext, jsonl_file, force } => {
+                commands::memory::export(&client, &resolve_bank(bank_id)?, out_dir, ext, jsonl_file, force, verbose, output_format)
             }
             MemoryCommands::Clear { bank_id, fact_type, yes } => {
-                commands::memory::clear(&client, &bank_id, fact_type, yes, verbose, output_format)
+                commands::memory::clear(&client, &resolve_bank(Some(bank_id))?, fact_type, yes, verbose, output_format)
             }
         },
 
         Commands::Document(doc_cmd) => match doc_cmd {
             DocumentCommands::List { bank_id, query, limit, offset } => {
-                commands::document::list(&client, &bank_id, query, limit, offset, verbose, output_format)
+                commands::document::list(&client, &resolve_bank(bank_id)?, query, limit, offset, verbose, output_format)
             }
             DocumentCommands::Get { bank_id, document_id } => {
-                commands::document::get(&client, &bank_id, &document_id, verbose, output_format)
-            }
-            DocumentCommands::Delete { bank_id, document_id } => {
-                commands::document::delete(&client, &bank_id, &document_id, verbose, output_format)
+                commands::document::get(&client, &resolve_bank(Some(bank_id))?, &document_id, verbose, output_format)
             }
+            DocumentCommands::Delete { bank_id, document_id, prefix, glob, yes } => match (document_id, prefix, glob) {
+                (Some(document_id), _, _) => commands::document::delete(&client, &resolve_bank(Some(bank_id))?, &document_id, verbose, output_format),
+                (None, Some(prefix), _) => {
+                    let matcher = commands::document::DocumentMatch::Prefix(prefix);
+                    commands::document::delete_matching(&client, &resolve_bank(Some(bank_id))?, &matcher, yes, verbose, output_format)
+                }
+                (None, None, Some(glob)) => {
+                    let matcher = commands::document::DocumentMatch::Glob(glob);
+                    commands::document::delete_matching(&client, &resolve_bank(Some(bank_id))?, &matcher, yes, verbose, output_format)
+                }
+                (None, None, None) => unreachable!(), // clap requires one of them
+            },
         },
 
         Commands::Entity(entity_cmd) => match entity_cmd {
             EntityCommands::List { bank_id, limit } => {
-                commands::entity::list(&client, &bank_id, limit, verbose, output_format)
+                commands::entity::list(&client, &resolve_bank(bank_id)?, limit, verbose, output_format)
             }
             EntityCommands::Get { bank_id, entity_id } => {
-                commands::entity::get(&client, &bank_id, &entity_id, verbose, output_format)
+                commands::entity::get(&client, &resolve_bank(Some(bank_id))?, &entity_id, verbose, output_format)
             }
             EntityCommands::Regenerate { bank_id, entity_id } => {
-                commands::entity::regenerate(&client, &bank_id, &entity_id, verbose, output_format)
+                commands::entity::regenerate(&client, &resolve_bank(Some(bank_id))?, &entity_id, verbose, output_format)
             }
         },
 
         Commands::Operation(op_cmd) => match op_cmd {
             OperationCommands::List { bank_id } => {
-                commands::operation::list(&client, &bank_id, verbose, output_format)
+                commands::operation::list(&client, &resolve_bank(bank_id)?, verbose, output_format)
+            }
+            OperationCommands::Status { bank_id, operation_id, wait } => {
+                commands::operation::status(&client, &resolve_bank(Some(bank_id))?, &operation_id, wait, verbose, output_format)
             }
             OperationCommands::Cancel { bank_id, operation_id } => {
-                commands::operation::cancel(&client, &bank_id, &operation_id, verbose, output_format)
+                commands::operation::cancel(&client, &resolve_bank(Some(bank_id))?, &operation_id, verbose, output_format)
             }
         },
     };
 
     // Handle API errors with nice messages
     if let Err(e) = result {
+        pager::finish();
         errors::handle_api_error(e, &api_url);
     }
 
+    if cli.timing && output_format == OutputFormat::Pretty && !is_explore {
+        ui::print_elapsed(started.elapsed());
+    }
+    pager::finish();
+
     Ok(())
 }
 
-fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_format: OutputFormat) -> Result<()> {
+fn print_completions(shell: clap_complete::Shell) {
+    let mut command = Cli::command();
+    let bin = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, &bin, &mut std::io::stdout());
+    if let Some(script) = completion::dynamic_bank_completion(shell, &bin) {
+        print!("{}", script);
+    }
+}
+
+fn complete_banks(client: &ApiClient, api_url: &str) {
+    let cache_path = completion::cache_file_path();
+    let cached = cache_path.as_deref().and_then(|path| completion::cached_bank_ids(path, api_url));
+    let bank_ids = match cached {
+        Some(bank_ids) => bank_ids,
+        None => {
+            let Ok(banks) = client.list_agents(Verbosity::default()) else {
+                return;
+            };
+            let bank_ids: Vec<String> = banks.into_iter().map(|bank| bank.bank_id).collect();
+            if let Some(path) = &cache_path {
+                let _ = completion::store_bank_ids(path, api_url, &bank_ids);
+            }
+            bank_ids
+        }
+    };
+    for bank_id in bank_ids {
+        println!("{}", bank_id);
+    }
+}
+
+fn handle_configure(api_url: Option<String>, api_key: Option<String>, config_path: Option<PathBuf>, output_format: OutputFormat) -> Result<()> {
     // Load current config to show current state
-    let current_config = Config::load().ok();
+    let overrides = ConfigOverrides {
+        config_path: config_path.clone(),
+        ..Default::default()
+    };
+    let current_config = Config::load_with(&overrides).ok();
 
     if output_format == OutputFormat::Pretty {
         ui::print_info("Hindsight CLI Configuration");
@@ -500,13 +1093,7 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
         if let Some(ref config) = current_config {
             println!("  Current API URL: {}", config.api_url);
             if let Some(ref key) = config.api_key {
-                // Mask the API key for display
-                let masked = if key.len() > 8 {
-                    format!("{}...{}", &key[..4], &key[key.len()-4..])
-                } else {
-                    "****".to_string()
-                };
-                println!("  Current API Key: {}", masked);
+                println!("  Current API Key: {}", config::mask_api_key(key));
             }
             println!("  Source: {}", config.source);
             println!();
@@ -529,26 +1116,24 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
             "Invalid API URL: {}. Must start with http:// or https://",
             new_api_url
         ));
-        return Ok(());
+        std::process::exit(errors::exit_code::CONFIG);
     }
 
     // Use provided api_key, or keep existing one if not provided
     let new_api_key = api_key.or_else(|| current_config.as_ref().and_then(|c| c.api_key.clone()));
 
     // Save to config file
-    let config_path = Config::save_config(&new_api_url, new_api_key.as_deref())?;
+    let config_path = match config_path {
+        Some(path) => Config::save_config_to(&path, &new_api_url, new_api_key.as_deref())?,
+        None => Config::save_config(&new_api_url, new_api_key.as_deref())?,
+    };
 
     if output_format == OutputFormat::Pretty {
         ui::print_success(&format!("Configuration saved to {}", config_path.display()));
         println!();
         println!("  API URL: {}", new_api_url);
         if let Some(ref key) = new_api_key {
-            let masked = if key.len() > 8 {
-                format!("{}...{}", &key[..4], &key[key.len()-4..])
-            } else {
-                "****".to_string()
-            };
-            println!("  API Key: {}", masked);
+            println!("  API Key: {}", config::mask_api_key(key));
         }
         println!();
         println!("Note: Environment variables HINDSIGHT_API_URL and HINDSIGHT_API_KEY will override these settings.");
@@ -564,14 +1149,14 @@ fn handle_configure(api_url: Option<String>, api_key: Option<String>, output_for
     Ok(())
 }
 
-fn handle_ui(output_format: OutputFormat) -> Result<()> {
+fn handle_ui(overrides: &ConfigOverrides, output_format: OutputFormat) -> Result<()> {
     use std::process::Command;
 
     // Load configuration to get the API URL
-    let config = Config::load().unwrap_or_else(|e| {
-        ui::print_error(&format!("Configuration error: {}", e));
+    let config = Config::load_with(overrides).unwrap_or_else(|e| {
+        ui::print_error(&format!("Configuration error: {:#}", e));
         errors::print_config_help();
-        std::process::exit(1);
+        std::process::exit(errors::exit_code::CONFIG);
     });
 
     let api_url = config.api_url();
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest file written into the directory passed to `retain-files`
+pub const MANIFEST_FILE: &str = ".hindsight-manifest.json";
+
+/// Content hashes of files already retained by `retain-files`, so re-runs only upload
+/// what changed. Hashes are tracked per bank: retaining the same folder into another
+/// bank uploads everything again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(skip)]
+    path: PathBuf,
+    /// bank ID -> file path (relative to the manifest's directory) -> SHA-256 hex digest
+    #[serde(default)]
+    banks: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Manifest {
+    /// An empty manifest that will be saved in `dir`
+    pub fn at(dir: &Path) -> Self {
+        Manifest { path: dir.join(MANIFEST_FILE), banks: BTreeMap::new() }
+    }
+
+    /// Load the manifest in `dir`, or start an empty one if there is none yet
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let mut manifest: Manifest = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid manifest {} (delete it or use --force)", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Manifest::at(dir)),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read manifest {}", path.display())),
+        };
+        manifest.path = path;
+        Ok(manifest)
+    }
+
+    /// True if `file` was retained into `bank_id` with exactly this content
+    pub fn is_unchanged(&self, bank_id: &str, file: &str, hash: &str) -> bool {
+        self.banks
+            .get(bank_id)
+            .and_then(|files| files.get(file))
+            .is_some_and(|recorded| recorded == hash)
+    }
+
+    pub fn record(&mut self, bank_id: &str, file: String, hash: String) {
+        self.banks.entry(bank_id.to_string()).or_default().insert(file, hash);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&self.path, format!("{}\n", content))
+            .with_context(|| format!("Failed to write manifest {}", self.path.display()))
+    }
+}
+
+/// SHA-256 of a file's content as lowercase hex
+pub fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b"hello"),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn test_manifest_round_trip_per_bank() {
+        let dir = std::env::temp_dir().join(format!("hindsight-cli-test-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut manifest = Manifest::load(&dir).unwrap();
+        assert!(!manifest.is_unchanged("notes", "a.md", "abc"));
+        manifest.record("notes", "a.md".to_string(), "abc".to_string());
+        manifest.save().unwrap();
+
+        let manifest = Manifest::load(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(manifest.is_unchanged("notes", "a.md", "abc"));
+        assert!(!manifest.is_unchanged("notes", "a.md", "def"));
+        assert!(!manifest.is_unchanged("other-bank", "a.md", "abc"));
+    }
+}
@@ -1,51 +1,275 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+// Global --out target; when set, structured output goes there instead of stdout
+static OUT_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+// Set by --timing: when the command started, reported as metadata in structured output
+static TIMING_START: OnceLock<Instant> = OnceLock::new();
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
     Pretty,
     Json,
+    /// JSON on a single line, without pretty-printing whitespace
+    JsonCompact,
+    Ndjson,
     Yaml,
+    Toml,
 }
 
-impl OutputFormat {
-    /// Parse output format from string
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "json" => Some(OutputFormat::Json),
-            "yaml" | "yml" => Some(OutputFormat::Yaml),
-            "pretty" | "text" => Some(OutputFormat::Pretty),
-            _ => None,
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    /// Parse a format name, as accepted by `output_format` in the config file.
+    /// Case-insensitive, with aliases: compact, jsonl, yml, and text or default for pretty.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "json-compact" | "compact" => Ok(OutputFormat::JsonCompact),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "toml" => Ok(OutputFormat::Toml),
+            "pretty" | "text" | "default" => Ok(OutputFormat::Pretty),
+            _ => anyhow::bail!(
+                "Unknown output format '{}' (expected pretty, json, json-compact, ndjson, yaml or toml)",
+                s
+            ),
         }
     }
 }
 
+/// The format a command prints in: `-o` if given, else `output_format` from the
+/// config, else pretty
+pub fn resolve_format(flag: Option<OutputFormat>, configured: Option<OutputFormat>) -> OutputFormat {
+    flag.or(configured).unwrap_or(OutputFormat::Pretty)
+}
+
 /// Format data as JSON string
 pub fn to_json<T: Serialize>(data: &T) -> Result<String> {
     Ok(serde_json::to_string_pretty(data)?)
 }
 
+/// Format data as a single line of JSON
+pub fn to_json_compact<T: Serialize>(data: &T) -> Result<String> {
+    Ok(serde_json::to_string(data)?)
+}
+
+/// Format data as newline-delimited JSON, one compact object per line.
+///
+/// Top-level arrays emit one line per element, and responses carrying a
+/// `results` array (recall) emit one line per result. Anything else is a
+/// single line.
+pub fn to_ndjson<T: Serialize>(data: &T) -> Result<String> {
+    let value = serde_json::to_value(data)?;
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(mut map) => match map.remove("results") {
+            Some(serde_json::Value::Array(results)) => results,
+            Some(other) => {
+                map.insert("results".to_string(), other);
+                vec![serde_json::Value::Object(map)]
+            }
+            None => vec![serde_json::Value::Object(map)],
+        },
+        other => vec![other],
+    };
+
+    let lines = items
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(lines.join("\n"))
+}
+
 /// Format data as YAML string
 pub fn to_yaml<T: Serialize>(data: &T) -> Result<String> {
     Ok(serde_yaml::to_string(data)?)
 }
 
-pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
-    match format {
-        OutputFormat::Json => {
-            println!("{}", to_json(data)?);
-        }
-        OutputFormat::Yaml => {
-            println!("{}", to_yaml(data)?);
+/// Format data as a TOML document.
+///
+/// TOML has no null, so null fields and array elements are left out. A document
+/// must be a table, so a top-level array is wrapped as `items = [...]` (any other
+/// non-table value as `value = ...`). Objects such as a recall response are
+/// emitted as is, with their `results` array as an array of tables.
+pub fn to_toml<T: Serialize>(data: &T) -> Result<String> {
+    let value = match strip_nulls(serde_json::to_value(data)?) {
+        value @ serde_json::Value::Object(_) => value,
+        items @ serde_json::Value::Array(_) => serde_json::json!({ "items": items }),
+        other => serde_json::json!({ "value": other }),
+    };
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+// Helper to drop nulls at any depth, since TOML can't represent them
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().filter(|v| !v.is_null()).map(strip_nulls).collect())
         }
+        other => other,
+    }
+}
+
+/// Render data in a structured format
+pub fn render<T: Serialize>(data: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => to_json(data),
+        OutputFormat::JsonCompact => to_json_compact(data),
+        OutputFormat::Ndjson => to_ndjson(data),
+        OutputFormat::Yaml => to_yaml(data),
+        OutputFormat::Toml => to_toml(data),
         OutputFormat::Pretty => {
             // This should not be called - pretty printing is handled in ui.rs
             unreachable!("Pretty format should be handled separately")
         }
     }
+}
+
+/// Send structured output to `path` instead of stdout. The file is created (or
+/// truncated) right away so an unwritable path fails before any request is made.
+pub fn set_out_path(path: PathBuf) -> Result<()> {
+    fs::File::create(&path).with_context(|| format!("Failed to create output file {}", path.display()))?;
+    let _ = OUT_PATH.set(path);
     Ok(())
 }
 
+/// Pretty output is printed piecemeal by each command, so with --out it reaches the
+/// file by pointing stdout there, without colors. Structured formats are written to
+/// the file by `print_output` and leave stdout alone. Called again once the config's
+/// `output_format` is known, which may undo the redirect.
+pub fn route_stdout(format: OutputFormat) -> Result<()> {
+    match OUT_PATH.get() {
+        Some(path) if format == OutputFormat::Pretty => stdout_redirect::to_file(path),
+        _ => {
+            stdout_redirect::restore();
+            Ok(())
+        }
+    }
+}
+
+#[cfg(unix)]
+mod stdout_redirect {
+    use anyhow::{Context, Result};
+    use std::fs::OpenOptions;
+    use std::io::{self, Write};
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    // Duplicate of the original stdout while it points at the --out file
+    static SAVED_STDOUT: Mutex<Option<i32>> = Mutex::new(None);
+
+    pub fn to_file(path: &Path) -> Result<()> {
+        let mut saved = SAVED_STDOUT.lock().unwrap_or_else(|e| e.into_inner());
+        if saved.is_some() {
+            return Ok(());
+        }
+        let file = OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open output file {}", path.display()))?;
+
+        let _ = io::stdout().flush();
+        // SAFETY: dup/dup2 on descriptors this process owns; `file` stays open until after dup2
+        let saved_stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved_stdout < 0 || unsafe { libc::dup2(file.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            let error = io::Error::last_os_error();
+            if saved_stdout >= 0 {
+                unsafe { libc::close(saved_stdout) };
+            }
+            return Err(error).with_context(|| format!("Failed to write output to {}", path.display()));
+        }
+        *saved = Some(saved_stdout);
+        colored::control::set_override(false);
+        Ok(())
+    }
+
+    pub fn restore() {
+        let Some(saved_stdout) = SAVED_STDOUT.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+            return;
+        };
+        let _ = io::stdout().flush();
+        // SAFETY: restores the descriptor saved in to_file()
+        unsafe {
+            libc::dup2(saved_stdout, libc::STDOUT_FILENO);
+            libc::close(saved_stdout);
+        }
+        colored::control::unset_override();
+    }
+}
+
+#[cfg(not(unix))]
+mod stdout_redirect {
+    use anyhow::Result;
+    use std::path::Path;
+
+    pub fn to_file(_path: &Path) -> Result<()> {
+        anyhow::bail!("--out can't capture pretty output on this platform; pick a format with -o, e.g. -o json")
+    }
+
+    pub fn restore() {}
+}
+
+// Helper to append one rendered block to the --out file
+fn append_line(path: &Path, text: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open output file {}", path.display()))?;
+    writeln!(file, "{}", text).with_context(|| format!("Failed to write output file {}", path.display()))
+}
+
+/// Report the time since `started` in json/yaml output, which is then wrapped as
+/// `{"data": ..., "metadata": {"elapsed_ms": ...}}`. ndjson is left as is.
+pub fn set_timing_start(started: Instant) {
+    let _ = TIMING_START.set(started);
+}
+
+#[derive(Serialize)]
+struct Timed<'a, T> {
+    data: &'a T,
+    metadata: TimingMetadata,
+}
+
+#[derive(Serialize)]
+struct TimingMetadata {
+    elapsed_ms: u64,
+}
+
+pub fn print_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<()> {
+    let rendered = match TIMING_START.get() {
+        Some(started) if format != OutputFormat::Ndjson => {
+            let metadata = TimingMetadata { elapsed_ms: started.elapsed().as_millis() as u64 };
+            render(&Timed { data, metadata }, format)?
+        }
+        _ => render(data, format)?,
+    };
+    if rendered.is_empty() {
+        return Ok(());
+    }
+    match OUT_PATH.get() {
+        Some(path) => append_line(path, &rendered),
+        None => {
+            println!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,31 +284,55 @@ mod tests {
 
     #[test]
     fn test_output_format_from_str_json() {
-        assert_eq!(OutputFormat::from_str("json"), Some(OutputFormat::Json));
-        assert_eq!(OutputFormat::from_str("JSON"), Some(OutputFormat::Json));
-        assert_eq!(OutputFormat::from_str("Json"), Some(OutputFormat::Json));
+        assert_eq!(OutputFormat::from_str("json").ok(), Some(OutputFormat::Json));
+        assert_eq!(OutputFormat::from_str("JSON").ok(), Some(OutputFormat::Json));
+        assert_eq!(OutputFormat::from_str("Json").ok(), Some(OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_output_format_from_str_json_compact() {
+        assert_eq!(OutputFormat::from_str("json-compact").ok(), Some(OutputFormat::JsonCompact));
+        assert_eq!(OutputFormat::from_str("COMPACT").ok(), Some(OutputFormat::JsonCompact));
+    }
+
+    #[test]
+    fn test_output_format_from_str_ndjson() {
+        assert_eq!(OutputFormat::from_str("ndjson").ok(), Some(OutputFormat::Ndjson));
+        assert_eq!(OutputFormat::from_str("JSONL").ok(), Some(OutputFormat::Ndjson));
     }
 
     #[test]
     fn test_output_format_from_str_yaml() {
-        assert_eq!(OutputFormat::from_str("yaml"), Some(OutputFormat::Yaml));
-        assert_eq!(OutputFormat::from_str("YAML"), Some(OutputFormat::Yaml));
-        assert_eq!(OutputFormat::from_str("yml"), Some(OutputFormat::Yaml));
-        assert_eq!(OutputFormat::from_str("YML"), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_str("yaml").ok(), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_str("YAML").ok(), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_str("yml").ok(), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_str("YML").ok(), Some(OutputFormat::Yaml));
     }
 
     #[test]
     fn test_output_format_from_str_pretty() {
-        assert_eq!(OutputFormat::from_str("pretty"), Some(OutputFormat::Pretty));
-        assert_eq!(OutputFormat::from_str("PRETTY"), Some(OutputFormat::Pretty));
-        assert_eq!(OutputFormat::from_str("text"), Some(OutputFormat::Pretty));
+        assert_eq!(OutputFormat::from_str("pretty").ok(), Some(OutputFormat::Pretty));
+        assert_eq!(OutputFormat::from_str("PRETTY").ok(), Some(OutputFormat::Pretty));
+        assert_eq!(OutputFormat::from_str("text").ok(), Some(OutputFormat::Pretty));
+        assert_eq!(OutputFormat::from_str("Default").ok(), Some(OutputFormat::Pretty));
     }
 
     #[test]
     fn test_output_format_from_str_invalid() {
-        assert_eq!(OutputFormat::from_str("xml"), None);
-        assert_eq!(OutputFormat::from_str("csv"), None);
-        assert_eq!(OutputFormat::from_str(""), None);
+        assert_eq!(OutputFormat::from_str("xml").ok(), None);
+        assert_eq!(OutputFormat::from_str("csv").ok(), None);
+        assert_eq!(OutputFormat::from_str("").ok(), None);
+        let err = "xml".parse::<OutputFormat>().unwrap_err().to_string();
+        assert!(err.contains("'xml'") && err.contains("json-compact"), "{}", err);
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        assert_eq!(resolve_format(None, None), OutputFormat::Pretty);
+        assert_eq!(resolve_format(None, Some(OutputFormat::Yaml)), OutputFormat::Yaml);
+        // -o beats the config file
+        assert_eq!(resolve_format(Some(OutputFormat::Pretty), Some(OutputFormat::Yaml)), OutputFormat::Pretty);
+        assert_eq!(resolve_format(Some(OutputFormat::Toml), None), OutputFormat::Toml);
     }
 
     #[test]
@@ -124,6 +372,82 @@ mod tests {
         assert!(json.contains("\"name\": \"b\""));
     }
 
+    #[test]
+    fn test_to_ndjson_array() {
+        let data = vec![
+            TestData { name: "a".to_string(), count: 1, active: true },
+            TestData { name: "b".to_string(), count: 2, active: false },
+        ];
+        let ndjson = to_ndjson(&data).unwrap();
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"name":"a","count":1,"active":true}"#);
+        assert_eq!(lines[1], r#"{"name":"b","count":2,"active":false}"#);
+    }
+
+    #[test]
+    fn test_to_ndjson_results_object() {
+        let data = serde_json::json!({
+            "results": [{"id": "1"}, {"id": "2"}, {"id": "3"}],
+            "trace": null,
+        });
+        let ndjson = to_ndjson(&data).unwrap();
+        assert_eq!(ndjson.lines().count(), 3);
+        assert_eq!(ndjson.lines().next().unwrap(), r#"{"id":"1"}"#);
+    }
+
+    #[test]
+    fn test_to_ndjson_single_object() {
+        let data = TestData { name: "test".to_string(), count: 42, active: true };
+        let ndjson = to_ndjson(&data).unwrap();
+        assert_eq!(ndjson, r#"{"name":"test","count":42,"active":true}"#);
+    }
+
+    #[test]
+    fn test_append_line_accumulates() {
+        let path = std::env::temp_dir().join(format!("hindsight-cli-test-out-{}.json", std::process::id()));
+        fs::write(&path, "").unwrap();
+        append_line(&path, "{\"a\":1}").unwrap();
+        append_line(&path, "{\"b\":2}").unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written, "{\"a\":1}\n{\"b\":2}\n");
+    }
+
+    #[test]
+    fn test_to_ndjson_empty_results() {
+        let data = serde_json::json!({ "results": [] });
+        assert_eq!(to_ndjson(&data).unwrap(), "");
+    }
+
+    #[test]
+    fn test_to_toml_round_trip() {
+        let data = TestData { name: "test".to_string(), count: 42, active: true };
+        let toml = to_toml(&data).unwrap();
+        assert_eq!(toml::from_str::<TestData>(&toml).unwrap(), data);
+    }
+
+    #[test]
+    fn test_to_toml_wraps_array_and_drops_nulls() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Wrapped {
+            items: Vec<TestData>,
+        }
+
+        let data = vec![
+            TestData { name: "a".to_string(), count: 1, active: true },
+            TestData { name: "b".to_string(), count: 2, active: false },
+        ];
+        let wrapped: Wrapped = toml::from_str(&to_toml(&data).unwrap()).unwrap();
+        assert_eq!(wrapped.items, data);
+
+        let response = serde_json::json!({ "results": [{ "id": "1", "context": null }], "trace": null });
+        let toml = to_toml(&response).unwrap();
+        assert!(!toml.contains("trace"));
+        assert!(!toml.contains("context"));
+        assert!(toml.contains("[[results]]"));
+    }
+
     #[test]
     fn test_to_yaml_array() {
         let data = vec![
@@ -162,4 +486,19 @@ mod tests {
         assert!(json.contains("\\\\"));
         assert!(json.contains("\\n"));
     }
+
+    #[test]
+    fn test_render_matches_format() {
+        let data = vec![
+            TestData { name: "a".to_string(), count: 1, active: true },
+            TestData { name: "b".to_string(), count: 2, active: false },
+        ];
+        assert_eq!(render(&data, OutputFormat::Json).unwrap(), to_json(&data).unwrap());
+        assert_eq!(render(&data, OutputFormat::Yaml).unwrap(), to_yaml(&data).unwrap());
+        assert_eq!(render(&data, OutputFormat::Ndjson).unwrap().lines().count(), 2);
+        assert_eq!(
+            render(&data, OutputFormat::JsonCompact).unwrap(),
+            r#"[{"name":"a","count":1,"active":true},{"name":"b","count":2,"active":false}]"#
+        );
+    }
 }
@@ -0,0 +1,136 @@
+//! Page long pretty output through `$PAGER`
+//!
+//! Commands that can print hundreds of lines (recall, reflect) call [`start`] right
+//! before printing, once their spinner is gone. stdout is then redirected into the
+//! pager until [`finish`] runs at the end of the command. The default `less -FRX`
+//! keeps colors and exits straight away when the output fits on one screen.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Cleared by the global --no-pager flag
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Allow or forbid paging for this invocation
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The pager to run: `$PAGER` split on whitespace, or `less -FRX`. `None` when paging
+/// is turned off with an empty `PAGER` or `PAGER=cat`.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn pager_command(pager_env: Option<&str>) -> Option<(String, Vec<String>)> {
+    let Some(pager) = pager_env else {
+        return Some(("less".to_string(), vec!["-FRX".to_string()]));
+    };
+    let mut parts = pager.split_whitespace().map(str::to_string);
+    let program = parts.next()?;
+    if program == "cat" {
+        return None;
+    }
+    Some((program, parts.collect()))
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io::{self, IsTerminal, Write};
+    use std::os::fd::AsRawFd;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::Mutex;
+
+    struct Pager {
+        child: Child,
+        // Duplicate of the original stdout, restored by finish()
+        saved_stdout: i32,
+    }
+
+    static PAGER: Mutex<Option<Pager>> = Mutex::new(None);
+
+    /// True while stdout is redirected into the pager
+    pub fn is_active() -> bool {
+        PAGER.lock().map(|pager| pager.is_some()).unwrap_or(false)
+    }
+
+    /// Send the rest of stdout through the pager, if paging is enabled and stdout is a terminal
+    pub fn start() {
+        if !super::is_enabled() || !io::stdout().is_terminal() || is_active() {
+            return;
+        }
+        let Some((program, args)) = super::pager_command(std::env::var("PAGER").ok().as_deref()) else {
+            return;
+        };
+
+        // Decide on colors while stdout is still the terminal
+        colored::control::set_override(colored::control::SHOULD_COLORIZE.should_colorize());
+
+        let Ok(mut child) = Command::new(&program).args(&args).stdin(Stdio::piped()).spawn() else {
+            // No pager available: just print to the terminal
+            return;
+        };
+        let Some(stdin) = child.stdin.take() else {
+            return;
+        };
+
+        let _ = io::stdout().flush();
+        // SAFETY: dup/dup2 on descriptors this process owns; `stdin` stays open until after dup2
+        let saved_stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved_stdout < 0 || unsafe { libc::dup2(stdin.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            let _ = child.kill();
+            return;
+        }
+        drop(stdin);
+
+        if let Ok(mut pager) = PAGER.lock() {
+            *pager = Some(Pager { child, saved_stdout });
+        }
+    }
+
+    /// Close the pager's input and wait for the user to quit it
+    pub fn finish() {
+        let Some(mut pager) = PAGER.lock().ok().and_then(|mut pager| pager.take()) else {
+            return;
+        };
+        let _ = io::stdout().flush();
+        // SAFETY: restores the descriptor saved in start(), which closes our end of the pipe.
+        // Ctrl-C is left to the pager, as git does, so we don't exit while it owns the terminal.
+        unsafe {
+            libc::dup2(pager.saved_stdout, libc::STDOUT_FILENO);
+            libc::close(pager.saved_stdout);
+            libc::signal(libc::SIGINT, libc::SIG_IGN);
+        }
+        let _ = pager.child.wait();
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    pub fn is_active() -> bool {
+        false
+    }
+
+    pub fn start() {}
+
+    pub fn finish() {}
+}
+
+pub use imp::{finish, is_active, start};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None), Some(("less".to_string(), vec!["-FRX".to_string()])));
+        assert_eq!(
+            pager_command(Some("most -s")),
+            Some(("most".to_string(), vec!["-s".to_string()]))
+        );
+        assert_eq!(pager_command(Some("")), None);
+        assert_eq!(pager_command(Some("cat")), None);
+    }
+}
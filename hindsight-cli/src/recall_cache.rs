@@ -0,0 +1,164 @@
+//! Local copies of recall results, so they can be viewed again without the server
+//!
+//! `recall --cache` saves each bank's response under the user's cache directory and
+//! `recall --offline` reads it back instead of calling the API. Entries are keyed by
+//! a hash of the server URL, bank ID, query and fact types, and are ignored once they
+//! are older than `--cache-ttl`.
+
+use anyhow::{Context, Result};
+use hindsight_client::types::{RecallRequest, RecallResponse};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CACHE_DIR_NAME: &str = "hindsight";
+const RECALL_DIR_NAME: &str = "recall";
+
+/// Default age after which a cached response is no longer served (one day)
+pub const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// One cached response, stored as `<key>.json`. Generic so a borrowed response
+/// can be written without cloning it.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedRecall<R> {
+    /// Unix time in seconds when the response was fetched
+    fetched_at: u64,
+    response: R,
+}
+
+/// Cache directory (e.g. ~/.cache/hindsight/recall on Linux)
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(CACHE_DIR_NAME).join(RECALL_DIR_NAME))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// File name stem for a recall: SHA-256 of the server, bank and every request field
+/// that changes the results (query, fact types, budget, token limit, includes, trace, tags)
+pub fn cache_key(api_url: &str, bank_id: &str, request: &RecallRequest) -> String {
+    let mut types = request.types.clone().unwrap_or_default();
+    types.sort();
+    let key = serde_json::json!([
+        api_url,
+        bank_id,
+        request.query,
+        types,
+        request.budget,
+        request.max_tokens,
+        request.include,
+        request.trace,
+        request.query_timestamp,
+        request.tags,
+        request.tags_match,
+    ]);
+    Sha256::digest(key.to_string().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The cached response for `key`, or `None` if there is none or it is older than `ttl`
+pub fn load(dir: &Path, key: &str, ttl: Duration) -> Result<Option<RecallResponse>> {
+    let path = dir.join(format!("{}.json", key));
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read cached results {}", path.display())),
+    };
+    // An entry written by another version is just a cache miss
+    let Ok(entry) = serde_json::from_str::<CachedRecall<RecallResponse>>(&content) else {
+        return Ok(None);
+    };
+    if now_secs().saturating_sub(entry.fetched_at) > ttl.as_secs() {
+        return Ok(None);
+    }
+    Ok(Some(entry.response))
+}
+
+/// Save a freshly fetched response under `key`
+pub fn store(dir: &Path, key: &str, response: &RecallResponse) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+    let entry = CachedRecall { fetched_at: now_secs(), response };
+    let path = dir.join(format!("{}.json", key));
+    fs::write(&path, serde_json::to_string(&entry)?)
+        .with_context(|| format!("Failed to write cached results {}", path.display()))
+}
+
+/// Delete every cached response; returns how many there were
+pub fn clear(dir: &Path) -> Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read cache directory {}", dir.display())),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> RecallResponse {
+        serde_json::from_value(serde_json::json!({ "results": [] })).unwrap()
+    }
+
+    fn request(query: &str, types: &[&str]) -> RecallRequest {
+        request_with(query, types, serde_json::json!({}))
+    }
+
+    fn request_with(query: &str, types: &[&str], extra: serde_json::Value) -> RecallRequest {
+        let mut value = serde_json::json!({
+            "query": query,
+            "types": types,
+        });
+        value.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("http://localhost:8888", "notes", &request("hiking", &["world", "experience"]));
+        // Fact type order doesn't matter
+        assert_eq!(key, cache_key("http://localhost:8888", "notes", &request("hiking", &["experience", "world"])));
+        assert_ne!(key, cache_key("http://localhost:8888", "work", &request("hiking", &["world", "experience"])));
+        assert_ne!(key, cache_key("http://localhost:8888", "notes", &request("hiking", &["world"])));
+        // Recall options change the results, so they get their own entries
+        let types = &["world", "experience"];
+        for extra in [
+            serde_json::json!({ "budget": "high" }),
+            serde_json::json!({ "max_tokens": 8192 }),
+            serde_json::json!({ "include": { "chunks": {} } }),
+            serde_json::json!({ "trace": true }),
+        ] {
+            let other = cache_key("http://localhost:8888", "notes", &request_with("hiking", types, extra.clone()));
+            assert_ne!(key, other, "{}", extra);
+        }
+    }
+
+    #[test]
+    fn test_store_load_clear() {
+        let dir = std::env::temp_dir().join(format!("hindsight-cli-test-recall-cache-{}", std::process::id()));
+        let day = Duration::from_secs(DEFAULT_TTL_SECS);
+
+        assert!(load(&dir, "abc", day).unwrap().is_none());
+        store(&dir, "abc", &response()).unwrap();
+        assert!(load(&dir, "abc", day).unwrap().is_some());
+        // Entries older than the TTL are treated as missing
+        let old = CachedRecall { fetched_at: 0, response: response() };
+        fs::write(dir.join("old.json"), serde_json::to_string(&old).unwrap()).unwrap();
+        assert!(load(&dir, "old", day).unwrap().is_none());
+
+        assert_eq!(clear(&dir).unwrap(), 2);
+        assert!(load(&dir, "abc", day).unwrap().is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
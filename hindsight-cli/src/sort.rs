@@ -0,0 +1,150 @@
+use chrono::{DateTime, FixedOffset};
+use clap::ValueEnum;
+use std::collections::HashSet;
+
+use crate::api::RecallResult;
+
+/// Client-side ordering for recall results.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SortBy {
+    /// Keep the order returned by the server
+    None,
+    /// Newest `occurred_start` first; results without a date go last
+    Date,
+}
+
+/// Gives sorting access to the recall result inside plain and bank-tagged results.
+pub trait AsRecallResult {
+    fn recall_result(&self) -> &RecallResult;
+}
+
+impl AsRecallResult for RecallResult {
+    fn recall_result(&self) -> &RecallResult {
+        self
+    }
+}
+
+/// Sort recall results in place. Applied before both pretty and structured output.
+///
+/// With `stable_order`, results that tie on the sort key are ordered by ID, then
+/// text, so repeated runs print the same order. Only ties are reordered. Results carry
+/// no score, so the server's own ranking (`SortBy::None`) has no key to tie on; the
+/// recall commands reject `--stable-order` without `--sort date`.
+pub fn sort_results<T: AsRecallResult>(results: &mut [T], sort_by: SortBy, stable_order: bool) {
+    match sort_by {
+        SortBy::None => {}
+        SortBy::Date if stable_order => sort_newest_first(
+            results,
+            |r| r.recall_result().occurred_start.as_deref(),
+            |r| (r.recall_result().id.clone(), r.recall_result().text.clone()),
+        ),
+        SortBy::Date => sort_newest_first(results, |r| r.recall_result().occurred_start.as_deref(), |_| ()),
+    }
+}
+
+/// Drop results repeating an earlier result's ID or text (compared case-insensitively,
+/// ignoring extra whitespace). The first, best-ranked copy is kept. Returns how many
+/// results were removed.
+pub fn dedup_results<T: AsRecallResult>(results: &mut Vec<T>) -> usize {
+    dedup_by_id_or_text(results, |r| (r.recall_result().id.as_str(), r.recall_result().text.as_str()))
+}
+
+fn dedup_by_id_or_text<T>(items: &mut Vec<T>, key: impl Fn(&T) -> (&str, &str)) -> usize {
+    let before = items.len();
+    let mut seen_ids = HashSet::new();
+    let mut seen_texts = HashSet::new();
+    items.retain(|item| {
+        let (id, text) = key(item);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let new_id = id.is_empty() || seen_ids.insert(id.to_string());
+        let new_text = seen_texts.insert(text);
+        new_id && new_text
+    });
+    before - items.len()
+}
+
+// Stable sort, newest first, then by `tiebreak` for equal dates. Missing or
+// unparseable dates sort last.
+fn sort_newest_first<T, K: Ord>(items: &mut [T], date: impl Fn(&T) -> Option<&str>, tiebreak: impl Fn(&T) -> K) {
+    items.sort_by_cached_key(|item| (std::cmp::Reverse(date(item).and_then(parse_date)), tiebreak(item)));
+}
+
+fn parse_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(dates: &[Option<&'static str>]) -> Vec<Option<&'static str>> {
+        let mut items = dates.to_vec();
+        sort_newest_first(&mut items, |d| *d, |_| ());
+        items
+    }
+
+    #[test]
+    fn test_dedup_by_id_or_text() {
+        let mut items = vec![
+            ("1", "Alice works at Google"),
+            ("2", "alice  works at google"),
+            ("1", "Alice moved to Paris"),
+            ("", "Bob likes hiking"),
+            ("", "Bob likes hiking"),
+            ("3", "Carol joined"),
+        ];
+        assert_eq!(dedup_by_id_or_text(&mut items, |item| *item), 3);
+        assert_eq!(items, vec![("1", "Alice works at Google"), ("", "Bob likes hiking"), ("3", "Carol joined")]);
+    }
+
+    #[test]
+    fn test_sort_newest_first() {
+        let items = sorted(&[
+            Some("2024-01-01T00:00:00Z"),
+            Some("2024-03-01T00:00:00Z"),
+            Some("2024-02-01T00:00:00Z"),
+        ]);
+        assert_eq!(
+            items,
+            vec![
+                Some("2024-03-01T00:00:00Z"),
+                Some("2024-02-01T00:00:00Z"),
+                Some("2024-01-01T00:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_compares_across_offsets() {
+        let items = sorted(&[Some("2024-01-01T10:00:00+02:00"), Some("2024-01-01T09:00:00Z")]);
+        assert_eq!(items, vec![Some("2024-01-01T09:00:00Z"), Some("2024-01-01T10:00:00+02:00")]);
+    }
+
+    #[test]
+    fn test_sort_missing_and_invalid_dates_last() {
+        let items = sorted(&[None, Some("not a date"), Some("2024-01-01T00:00:00Z")]);
+        assert_eq!(items, vec![Some("2024-01-01T00:00:00Z"), None, Some("not a date")]);
+    }
+
+    #[test]
+    fn test_sort_tiebreak_only_reorders_ties() {
+        let mut items = vec![
+            (Some("2024-01-01T00:00:00Z"), "b"),
+            (Some("2024-02-01T00:00:00Z"), "c"),
+            (None, "z"),
+            (Some("2024-01-01T00:00:00Z"), "a"),
+            (None, "y"),
+        ];
+        sort_newest_first(&mut items, |item| item.0, |item| item.1);
+        assert_eq!(
+            items,
+            vec![
+                (Some("2024-02-01T00:00:00Z"), "c"),
+                (Some("2024-01-01T00:00:00Z"), "a"),
+                (Some("2024-01-01T00:00:00Z"), "b"),
+                (None, "y"),
+                (None, "z"),
+            ]
+        );
+    }
+}
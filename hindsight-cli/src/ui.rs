@@ -1,8 +1,8 @@
-use crate::api::{BankProfileResponse, RecallResult, RecallResponse, ReflectResponse};
+use crate::api::{types, BankProfileResponse, BankRecallResult, BankSummary, QueryRecallResult, RecallResult, RecallResponse, ReflectResponse, TraceInfo};
 use colored::*;
 use hindsight_client::types::ChunkData;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 
 /// The logo as ANSI-colored text, generated by test-logo.py
 const LOGO: &str = include_str!("logo.ansi");
@@ -11,6 +11,77 @@ const LOGO: &str = include_str!("logo.ansi");
 const GRADIENT_START: (u8, u8, u8) = (0, 116, 217);  // #0074d9
 const GRADIENT_END: (u8, u8, u8) = (0, 146, 150);    // #009296
 
+// Set by the global --quiet flag: hides spinners, progress bars and status messages
+static QUIET: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Suppress spinners, progress bars and info/success/warning messages.
+/// Results and errors are still printed.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, std::sync::atomic::Ordering::Relaxed);
+}
+
+fn is_quiet() -> bool {
+    QUIET.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+// Set by the global --absolute-dates flag: print fact dates as returned by the API
+static ABSOLUTE_DATES: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Print fact dates verbatim instead of "3 days ago" for recent ones.
+pub fn set_absolute_dates(absolute: bool) {
+    ABSOLUTE_DATES.store(absolute, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// `--table-width`: the widest a table may get before its ID column is shortened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableWidth {
+    /// The terminal's width
+    Auto,
+    Columns(usize),
+}
+
+impl TableWidth {
+    /// Parse `auto` or a column count. Used as a clap value parser.
+    pub fn parse(arg: &str) -> std::result::Result<Self, String> {
+        if arg.eq_ignore_ascii_case("auto") {
+            return Ok(TableWidth::Auto);
+        }
+        match arg.parse::<usize>() {
+            Ok(columns) if columns >= MIN_TABLE_WIDTH => Ok(TableWidth::Columns(columns)),
+            _ => Err(format!("expected \"auto\" or a number of columns (at least {})", MIN_TABLE_WIDTH)),
+        }
+    }
+}
+
+// Set by the global --table-width flag; tables are as wide as their content when unset
+static TABLE_WIDTH: std::sync::OnceLock<TableWidth> = std::sync::OnceLock::new();
+
+/// Bound table width for this invocation
+pub fn set_table_width(width: Option<TableWidth>) {
+    if let Some(width) = width {
+        let _ = TABLE_WIDTH.set(width);
+    }
+}
+
+fn table_width_limit() -> Option<usize> {
+    match TABLE_WIDTH.get()? {
+        TableWidth::Auto => Some(terminal_width()),
+        TableWidth::Columns(columns) => Some(*columns),
+    }
+}
+
+// Narrowest --table-width accepted, and narrowest a shortened column gets
+const MIN_TABLE_WIDTH: usize = 20;
+const MIN_SHORTENED_COLUMN: usize = 8;
+
+// Dates further away than this are printed as-is
+const RELATIVE_DATE_DAYS: i64 = 30;
+
+// Width used for wrapping when the terminal size is unknown (e.g. piped output)
+const FALLBACK_WIDTH: usize = 80;
+// Never wrap narrower than this, however deep the indent
+const MIN_WRAP_WIDTH: usize = 20;
+
 /// Interpolate between two RGB colors
 fn interpolate_color(start: (u8, u8, u8), end: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
     (
@@ -77,8 +148,307 @@ pub fn print_section_header(title: &str) {
     println!();
 }
 
-pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
-    let fact_type = fact.type_.as_deref().unwrap_or("unknown");
+/// Width to wrap text to: the terminal width, or 80 columns when stdout isn't a terminal
+/// (output going through the pager still ends up on the terminal)
+fn terminal_width() -> usize {
+    if !io::stdout().is_terminal() && !crate::pager::is_active() {
+        return FALLBACK_WIDTH;
+    }
+    crossterm::terminal::size()
+        .map(|(cols, _)| cols as usize)
+        .unwrap_or(FALLBACK_WIDTH)
+}
+
+/// Greedy word wrap. Existing line breaks and each line's leading whitespace are
+/// kept (continuation lines reuse it); words longer than `width` get their own line.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        let lead = &line[..line.len() - line.trim_start().len()];
+        let lead_len = lead.chars().count();
+        let mut current = lead.to_string();
+        let mut current_len = lead_len;
+
+        for word in line.split_whitespace() {
+            let word_len = word.chars().count();
+            if current_len > lead_len && current_len + 1 + word_len > width {
+                lines.push(std::mem::replace(&mut current, lead.to_string()));
+                current_len = lead_len;
+            }
+            if current_len > lead_len {
+                current.push(' ');
+                current_len += 1;
+            }
+            current.push_str(word);
+            current_len += word_len;
+        }
+
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Print text wrapped to the terminal width, with every line indented by `indent` spaces
+pub fn print_wrapped(text: &str, indent: usize) {
+    print_wrapped_highlighted(text, indent, &[]);
+}
+
+// Like print_wrapped, but emphasizes `terms` after wrapping so escape codes don't skew widths
+fn print_wrapped_highlighted(text: &str, indent: usize, terms: &[String]) {
+    let prefix = " ".repeat(indent);
+    let width = terminal_width().saturating_sub(indent).max(MIN_WRAP_WIDTH);
+    for line in wrap_text(text, width) {
+        println!("{}{}", prefix, highlight_terms(&line, terms));
+    }
+}
+
+/// Split a query into lowercase terms for highlighting, dropping duplicates
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split_whitespace().map(str::to_lowercase) {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+// Helper to measure a case-insensitive match of `term` (already lowercase) at the start of
+// `text`. Returns the matched length in bytes of `text`, always ending on a char boundary.
+fn match_len(text: &str, term: &str) -> Option<usize> {
+    let mut want = term.chars().peekable();
+    want.peek()?;
+
+    for (i, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            if want.next() != Some(lower) {
+                return None;
+            }
+        }
+        if want.peek().is_none() {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+// Helper to bold and underline every occurrence of `terms` in `line`, preferring the longest match
+fn highlight_terms(line: &str, terms: &[String]) -> String {
+    if terms.is_empty() {
+        return line.to_string();
+    }
+
+    let mut out = String::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+
+    while pos < line.len() {
+        let rest = &line[pos..];
+        match terms.iter().filter_map(|term| match_len(rest, term)).max() {
+            Some(len) => {
+                out.push_str(&line[plain_start..pos]);
+                out.push_str(&rest[..len].bold().underline().to_string());
+                pos += len;
+                plain_start = pos;
+            }
+            None => pos += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    out.push_str(&line[plain_start..]);
+    out
+}
+
+// Helper to read a Markdown ATX heading ("## Title") and return its text
+fn markdown_heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && line[level..].starts_with(' ') {
+        Some(line[level..].trim())
+    } else {
+        None
+    }
+}
+
+// Helper to split a Markdown list item into the marker to display and its text
+fn markdown_list_item(line: &str) -> Option<(&str, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("•", text));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && line[digits..].starts_with(". ") {
+        return Some((&line[..digits + 1], &line[digits + 2..]));
+    }
+    None
+}
+
+// Helper to style inline **bold** and `code` spans. Unclosed delimiters are left as-is.
+fn style_inline(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['*', '`']) {
+        let delim = if rest[start..].starts_with("**") {
+            "**"
+        } else if rest[start..].starts_with('`') {
+            "`"
+        } else {
+            out.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        };
+
+        let inner_start = start + delim.len();
+        match rest[inner_start..].find(delim) {
+            Some(len) if len > 0 => {
+                let inner = &rest[inner_start..inner_start + len];
+                out.push_str(&rest[..start]);
+                if delim == "**" {
+                    out.push_str(&inner.bold().to_string());
+                } else {
+                    out.push_str(&gradient_end(inner));
+                }
+                rest = &rest[inner_start + len + delim.len()..];
+            }
+            _ => {
+                out.push_str(&rest[..inner_start]);
+                rest = &rest[inner_start..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Render Markdown for the terminal: headings, bullet and numbered lists (with hanging
+/// indents), fenced code blocks, and inline **bold** / `code`. Prose is word-wrapped to `width`.
+fn render_markdown(text: &str, width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            out.push(format!("  {}", dim(line)));
+            continue;
+        }
+
+        if let Some(heading) = markdown_heading(trimmed) {
+            out.push(gradient_text(heading).bold().to_string());
+        } else if let Some((marker, item)) = markdown_list_item(trimmed) {
+            let lead = " ".repeat(line.len() - trimmed.len());
+            let hang = " ".repeat(marker.chars().count() + 1);
+            let item_width = width.saturating_sub(lead.len() + hang.len()).max(MIN_WRAP_WIDTH);
+            for (i, wrapped) in wrap_text(item, item_width).iter().enumerate() {
+                let prefix = if i == 0 { format!("{} ", gradient_start(marker)) } else { hang.clone() };
+                out.push(format!("{}{}{}", lead, prefix, style_inline(wrapped)));
+            }
+        } else {
+            out.extend(wrap_text(line, width).iter().map(|wrapped| style_inline(wrapped)));
+        }
+    }
+
+    out
+}
+
+/// Print Markdown text styled for the terminal and wrapped to its width
+pub fn print_markdown(text: &str) {
+    for line in render_markdown(text, terminal_width().max(MIN_WRAP_WIDTH)) {
+        println!("{}", line);
+    }
+}
+
+// Helper to describe `date` relative to `now` ("5 minutes ago", "in 2 days"), or None
+// when it is more than RELATIVE_DATE_DAYS away
+fn relative_time(date: chrono::DateTime<chrono::Utc>, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let delta = now.signed_duration_since(date);
+    let seconds = delta.num_seconds().abs();
+    if delta.num_days().abs() > RELATIVE_DATE_DAYS {
+        return None;
+    }
+    if seconds < 60 {
+        return Some("just now".to_string());
+    }
+
+    let (count, unit) = if seconds < 3600 {
+        (seconds / 60, "minute")
+    } else if seconds < 86400 {
+        (seconds / 3600, "hour")
+    } else {
+        (seconds / 86400, "day")
+    };
+    let plural = if count == 1 { "" } else { "s" };
+
+    if delta.num_seconds() >= 0 {
+        Some(format!("{} {}{} ago", count, unit, plural))
+    } else {
+        Some(format!("in {} {}{}", count, unit, plural))
+    }
+}
+
+/// Render a date: relative when recent, otherwise (or when unparseable, or with
+/// --absolute-dates) the raw value
+pub fn format_fact_date(raw: &str) -> String {
+    if ABSOLUTE_DATES.load(std::sync::atomic::Ordering::Relaxed) {
+        return raw.to_string();
+    }
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .and_then(|date| relative_time(date.with_timezone(&chrono::Utc), chrono::Utc::now()))
+        .unwrap_or_else(|| raw.to_string())
+}
+
+/// Print a single fact. Occurrences of `highlight` terms (see `query_terms`) are emphasized.
+pub fn print_fact(fact: &RecallResult, _show_activation: bool, highlight: &[String]) {
+    print_fact_view(
+        &FactView {
+            fact_type: fact.type_.as_deref(),
+            text: &fact.text,
+            context: fact.context.as_deref(),
+            occurred_start: fact.occurred_start.as_deref(),
+            occurred_end: fact.occurred_end.as_deref(),
+            document_id: fact.document_id.as_deref(),
+        },
+        highlight,
+    );
+}
+
+// Helper function printing a fact a reflect answer was based on, laid out like a recall result
+fn print_reflect_fact(fact: &types::ReflectFact) {
+    print_fact_view(
+        &FactView {
+            fact_type: fact.type_.as_deref(),
+            text: &fact.text,
+            context: fact.context.as_deref(),
+            occurred_start: fact.occurred_start.as_deref(),
+            occurred_end: fact.occurred_end.as_deref(),
+            document_id: None,
+        },
+        &[],
+    );
+}
+
+// The fields of a recall result or reflect fact that get printed
+struct FactView<'a> {
+    fact_type: Option<&'a str>,
+    text: &'a str,
+    context: Option<&'a str>,
+    occurred_start: Option<&'a str>,
+    occurred_end: Option<&'a str>,
+    document_id: Option<&'a str>,
+}
+
+fn print_fact_view(fact: &FactView, highlight: &[String]) {
+    let fact_type = fact.fact_type.unwrap_or("unknown");
 
     // Use gradient positions for different fact types
     let type_t = match fact_type {
@@ -89,24 +459,25 @@ pub fn print_fact(fact: &RecallResult, _show_activation: bool) {
     };
 
     println!("{}", gradient(&format!("[{}]", fact_type.to_uppercase()), type_t));
-    println!("  {}", fact.text);
+    print_wrapped_highlighted(fact.text, 2, highlight);
 
     // Show context if available
-    if let Some(context) = &fact.context {
+    if let Some(context) = fact.context {
         println!("  {} {}", dim("context:"), dim(context));
     }
 
     // Show temporal information
-    if let Some(occurred_start) = &fact.occurred_start {
-        if let Some(occurred_end) = &fact.occurred_end {
-            println!("  {} {} - {}", dim("date:"), dim(occurred_start), dim(occurred_end));
+    if let Some(occurred_start) = fact.occurred_start {
+        let start = format_fact_date(occurred_start);
+        if let Some(occurred_end) = fact.occurred_end {
+            println!("  {} {} - {}", dim("date:"), dim(&start), dim(&format_fact_date(occurred_end)));
         } else {
-            println!("  {} {}", dim("date:"), dim(occurred_start));
+            println!("  {} {}", dim("date:"), dim(&start));
         }
     }
 
     // Show document ID if available
-    if let Some(document_id) = &fact.document_id {
+    if let Some(document_id) = fact.document_id {
         println!("  {} {}", dim("document:"), dim(document_id));
     }
 
@@ -135,16 +506,60 @@ pub fn print_chunk(chunk: &ChunkData) {
     println!();
 }
 
-pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_chunks: bool) {
+// Helper to partition result indices by fact type. Groups appear in order of first
+// occurrence and keep the original relative order; missing types group as "unknown".
+fn group_by_type<'a>(types: impl Iterator<Item = Option<&'a str>>) -> Vec<(&'a str, Vec<usize>)> {
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (i, fact_type) in types.enumerate() {
+        let fact_type = fact_type.unwrap_or("unknown");
+        match groups.iter_mut().find(|(t, _)| *t == fact_type) {
+            Some((_, indices)) => indices.push(i),
+            None => groups.push((fact_type, vec![i])),
+        }
+    }
+    groups
+}
+
+// Helper to print results in rank order, or under a header per fact type when grouping
+fn print_result_list(types: Vec<Option<&str>>, group: bool, mut print_one: impl FnMut(usize)) {
+    if !group {
+        (0..types.len()).for_each(print_one);
+        return;
+    }
+    for (fact_type, indices) in group_by_type(types.into_iter()) {
+        print_section_header(&format!("{} ({})", fact_type.to_uppercase(), indices.len()));
+        indices.into_iter().for_each(&mut print_one);
+    }
+}
+
+pub fn print_search_results(
+    response: &RecallResponse,
+    total: usize,
+    show_trace: bool,
+    show_chunks: bool,
+    highlight: &[String],
+    group_by_type: bool,
+) {
     let results = &response.results;
-    print_section_header(&format!("Search Results ({})", results.len()));
+    if results.len() < total {
+        print_section_header(&format!("Search Results ({} of {})", results.len(), total));
+    } else {
+        print_section_header(&format!("Search Results ({})", results.len()));
+    }
 
     if results.is_empty() {
         println!("  {}", dim("No results found."));
     } else {
-        for (i, fact) in results.iter().enumerate() {
-            println!("  {}", dim(&format!("Result #{}", i + 1)));
-            print_fact(fact, true);
+        let types = results.iter().map(|fact| fact.type_.as_deref()).collect();
+        print_result_list(types, group_by_type, |i| {
+            let fact = &results[i];
+            // Show the ID alongside the number so it can be passed to delete
+            if fact.id.is_empty() {
+                println!("  {}", dim(&format!("Result #{}", i + 1)));
+            } else {
+                println!("  {}", dim(&format!("Result #{} · {}", i + 1, fact.id)));
+            }
+            print_fact(fact, true, highlight);
 
             // Show chunk if available and requested
             if show_chunks {
@@ -156,7 +571,7 @@ pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_ch
                     }
                 }
             }
-        }
+        });
     }
 
     if show_trace {
@@ -166,13 +581,65 @@ pub fn print_search_results(response: &RecallResponse, show_trace: bool, show_ch
     }
 }
 
-pub fn print_think_response(response: &ReflectResponse) {
+pub fn print_multi_bank_results(results: &[BankRecallResult], total: usize, highlight: &[String], group_by_type: bool) {
+    if results.len() < total {
+        print_section_header(&format!("Search Results ({} of {})", results.len(), total));
+    } else {
+        print_section_header(&format!("Search Results ({})", results.len()));
+    }
+
+    if results.is_empty() {
+        println!("  {}", dim("No results found."));
+        return;
+    }
+
+    let types = results.iter().map(|r| r.result.type_.as_deref()).collect();
+    print_result_list(types, group_by_type, |i| {
+        let r = &results[i];
+        println!(
+            "  {} {}",
+            dim(&format!("Result #{} · {}", i + 1, r.result.id)),
+            gradient_mid(&format!("[{}]", r.bank_id))
+        );
+        print_fact(&r.result, true, highlight);
+    });
+}
+
+/// Print `recall --queries-file` results, one section per query
+pub fn print_query_results(batches: &[QueryRecallResult], highlight: bool) {
+    for (n, batch) in batches.iter().enumerate() {
+        print_section_header(&format!("Query {}: {} ({})", n + 1, batch.query, batch.results.len()));
+        if let Some(error) = &batch.error {
+            println!("  {}", format!("✗ {}", error).bright_red());
+            continue;
+        }
+        if batch.results.is_empty() {
+            println!("  {}", dim("No results found."));
+            continue;
+        }
+        let terms = if highlight { query_terms(&batch.query) } else { Vec::new() };
+        for (i, fact) in batch.results.iter().enumerate() {
+            println!("  {}", dim(&format!("Result #{} · {}", i + 1, fact.id)));
+            print_fact(fact, true, &terms);
+        }
+    }
+}
+
+/// Print a reflect answer. With `explain`, the facts and mental models it was based
+/// on are listed after it; otherwise only their count is shown.
+pub fn print_think_response(response: &ReflectResponse, markdown: bool, explain: bool) {
     print_section_header("Reflection");
 
-    println!("{}", response.text);
+    if markdown {
+        print_markdown(&response.text);
+    } else {
+        print_wrapped(&response.text, 0);
+    }
     println!();
 
-    if let Some(based_on) = &response.based_on {
+    if explain {
+        print_based_on(response.based_on.as_ref());
+    } else if let Some(based_on) = &response.based_on {
         let count = based_on.memories.len() + based_on.mental_models.len();
         if count > 0 {
             println!("{}", dim(&format!("Based on {} memory units", count)));
@@ -190,21 +657,110 @@ pub fn print_think_response(response: &ReflectResponse) {
     }
 }
 
+// Helper function for `reflect --explain`
+fn print_based_on(based_on: Option<&types::ReflectBasedOn>) {
+    let (memories, mental_models) = match based_on {
+        Some(based_on) => (based_on.memories.as_slice(), based_on.mental_models.as_slice()),
+        None => (&[][..], &[][..]),
+    };
+    print_section_header(&format!("Based On ({})", memories.len() + mental_models.len()));
+    if memories.is_empty() && mental_models.is_empty() {
+        println!("  {}", dim("The server didn't report any facts for this answer."));
+        println!();
+        return;
+    }
+    for fact in memories {
+        print_reflect_fact(fact);
+    }
+    for model in mental_models {
+        println!("{}", gradient(&format!("[MENTAL MODEL · {}]", model.type_.to_uppercase()), 1.0));
+        println!("  {}", model.name);
+        print_wrapped(&model.description, 2);
+        println!();
+    }
+}
+
 pub fn print_trace_info(trace: &serde_json::Map<String, serde_json::Value>) {
     print_section_header("Trace");
 
-    if let Some(time) = trace.get("total_time").and_then(|v| v.as_f64()) {
+    // A field of an unexpected type shouldn't hide the rest of the trace
+    let info: TraceInfo = serde_json::from_value(serde_json::Value::Object(trace.clone()))
+        .unwrap_or_else(|_| TraceInfo { extra: trace.clone(), ..Default::default() });
+
+    if let Some(time) = info.total_time {
         println!("  {} {}", dim("total time:"), gradient_start(&format!("{:.2}ms", time)));
     }
 
-    if let Some(count) = trace.get("activation_count").and_then(|v| v.as_i64()) {
+    if let Some(count) = info.activation_count {
         println!("  {} {}", dim("activation count:"), gradient_end(&count.to_string()));
     }
 
+    // Lists of objects (e.g. per-fact activations) get a small table each; other
+    // fields are listed as key/value lines
+    for (key, value) in &info.extra {
+        match value.as_array().and_then(|items| object_table(items)) {
+            Some((headers, rows)) => {
+                println!("  {}", dim(&format!("{}:", key)));
+                let right_align: Vec<bool> = (0..headers.len())
+                    .map(|i| rows.iter().all(|row| row[i] == "-" || row[i].parse::<f64>().is_ok()))
+                    .collect();
+                let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+                let mut lines = table_lines(&headers, &rows, &right_align).into_iter();
+                if let Some(header) = lines.next() {
+                    println!("    {}", header.bright_black());
+                }
+                for line in lines {
+                    println!("    {}", line);
+                }
+            }
+            None => println!("  {} {}", dim(&format!("{}:", key)), trace_cell(value)),
+        }
+    }
+
     println!();
 }
 
+// Helper function to show a trace value in one cell: strings unquoted, nested values as compact JSON
+fn trace_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "-".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Headers and rows for a list of trace objects, with a column for every key seen
+/// in any of them. `None` unless every item is an object.
+fn object_table(items: &[serde_json::Value]) -> Option<(Vec<String>, Vec<Vec<String>>)> {
+    if items.is_empty() {
+        return None;
+    }
+    let objects: Vec<&serde_json::Map<String, serde_json::Value>> =
+        items.iter().map(|item| item.as_object()).collect::<Option<_>>()?;
+    let mut headers: Vec<String> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+    }
+    let rows = objects
+        .iter()
+        .map(|object| {
+            headers
+                .iter()
+                .map(|key| object.get(key).map(trace_cell).unwrap_or_else(|| "-".to_string()))
+                .collect()
+        })
+        .collect();
+    Some((headers, rows))
+}
+
 pub fn print_success(message: &str) {
+    if is_quiet() {
+        return;
+    }
     println!("{}", gradient_start(message));
 }
 
@@ -213,13 +769,139 @@ pub fn print_error(message: &str) {
 }
 
 pub fn print_warning(message: &str) {
+    if is_quiet() {
+        return;
+    }
     println!("{} {}", gradient_end("warning:"), message);
 }
 
 pub fn print_info(message: &str) {
+    if is_quiet() {
+        return;
+    }
     println!("{}", gradient_start(message));
 }
 
+/// Print `bank list --detailed` as a table
+pub fn print_banks_table(banks: &[BankSummary]) {
+    let rows: Vec<Vec<String>> = banks
+        .iter()
+        .map(|summary| {
+            vec![
+                summary.bank.bank_id.clone(),
+                summary.bank.name.clone().unwrap_or_default(),
+                summary.memory_count.to_string(),
+                summary.document_count.to_string(),
+                summary.bank.updated_at.as_deref().map(format_fact_date).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    let headers = ["BANK ID", "NAME", "MEMORIES", "DOCUMENTS", "UPDATED"];
+    let mut rows = rows;
+    if let Some(limit) = table_width_limit() {
+        // Two columns of indent
+        fit_column(&headers, &mut rows, 0, limit.saturating_sub(2));
+    }
+    let lines = table_lines(&headers, &rows, &[false, false, true, true, false]);
+    if let Some((header, body)) = lines.split_first() {
+        println!("  {}", header.bright_black());
+        for line in body {
+            println!("  {}", line);
+        }
+    }
+}
+
+/// Shorten column `col` so the table laid out by [`table_lines`] is at most
+/// `max_width` characters wide, cutting longer cells with "…". Only that column
+/// shrinks, and never below its header or [`MIN_SHORTENED_COLUMN`].
+fn fit_column(headers: &[&str], rows: &mut [Vec<String>], col: usize, max_width: usize) {
+    let width_of = |i: usize| {
+        rows.iter()
+            .filter_map(|row| row.get(i))
+            .map(|cell| cell.chars().count())
+            .chain(std::iter::once(headers[i].chars().count()))
+            .max()
+            .unwrap_or(0)
+    };
+    let widths: Vec<usize> = (0..headers.len()).map(width_of).collect();
+    let total = widths.iter().sum::<usize>() + 2 * headers.len().saturating_sub(1);
+    if total <= max_width {
+        return;
+    }
+    let floor = headers[col].chars().count().max(MIN_SHORTENED_COLUMN);
+    let target = widths[col].saturating_sub(total - max_width).max(floor);
+    for row in rows.iter_mut() {
+        if let Some(cell) = row.get_mut(col) {
+            *cell = truncate_with_ellipsis(cell, target);
+        }
+    }
+}
+
+// Helper function cutting text to `width` characters, the last one an ellipsis.
+// Counts chars, so multibyte text can't be split mid-character.
+fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+/// Lay out a table as lines of text: a header line, then one line per row. Each
+/// column is as wide as its widest cell; `right_align` marks numeric columns.
+fn table_lines(headers: &[&str], rows: &[Vec<String>], right_align: &[bool]) -> Vec<String> {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(header.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let pad = " ".repeat(widths[i].saturating_sub(cell.chars().count()));
+                if right_align.get(i).copied().unwrap_or(false) {
+                    format!("{}{}", pad, cell)
+                } else {
+                    format!("{}{}", cell, pad)
+                }
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    std::iter::once(format_line(headers.to_vec()))
+        .chain(rows.iter().map(|row| format_line(row.iter().map(String::as_str).collect())))
+        .collect()
+}
+
+/// Print how long the command took (--timing). Not silenced by --quiet, since it was asked for.
+pub fn print_elapsed(elapsed: std::time::Duration) {
+    println!("{}", format!("Completed in {}", format_elapsed(elapsed)).bright_black());
+}
+
+/// "850ms" under a second, "2.35s" under a minute, "1m 05s" beyond that
+pub fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let millis = elapsed.as_millis();
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.2}s", elapsed.as_secs_f64())
+    } else {
+        let secs = elapsed.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
 /// Animated gradient spinner that shows text with moving gradient colors
 pub struct GradientSpinner {
     message: String,
@@ -273,10 +955,23 @@ impl GradientSpinner {
         }
     }
 
+    /// A spinner that never draws, used in quiet mode
+    fn hidden() -> Self {
+        Self {
+            message: String::new(),
+            running: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            handle: None,
+        }
+    }
+
     pub fn finish(&mut self) {
         self.running.store(false, std::sync::atomic::Ordering::Relaxed);
-        if let Some(handle) = self.handle.take() {
-            let _ = handle.join();
+        match self.handle.take() {
+            Some(handle) => {
+                let _ = handle.join();
+            }
+            // Never drawn (or already cleared), so there is nothing to clear
+            None => return,
         }
         // Clear the line
         print!("\r{}\r", " ".repeat(self.message.len() + 10));
@@ -293,10 +988,16 @@ impl Drop for GradientSpinner {
 }
 
 pub fn create_spinner(message: &str) -> GradientSpinner {
+    if is_quiet() {
+        return GradientSpinner::hidden();
+    }
     GradientSpinner::new(message)
 }
 
 pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
     let pb = ProgressBar::new(total);
     pb.set_style(
         ProgressStyle::default_bar()
@@ -308,9 +1009,44 @@ pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
     pb
 }
 
+/// Progress bar measured in bytes, with the transfer rate, so one large file still
+/// shows movement. Put item counts in the message.
+pub fn create_bytes_progress_bar(total_bytes: u64, message: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(total_bytes);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")
+            .unwrap()
+            .progress_chars("█▓▒░ "),
+    );
+    pb.set_message(message.to_string());
+    pb
+}
+
+/// Progress bar that also shows how many items per second are going through,
+/// for transfers long enough that the rate matters
+pub fn create_rate_progress_bar(total: u64, message: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} ({percent}%, {per_sec})")
+            .unwrap()
+            .progress_chars("█▓▒░ "),
+    );
+    pb.set_message(message.to_string());
+    pb
+}
+
 pub fn prompt_confirmation(message: &str) -> io::Result<bool> {
-    print!("{} [y/N]: ", gradient_start(message));
-    io::stdout().flush()?;
+    // On stderr, so it stays visible when stdout goes to a file
+    eprint!("{} [y/N]: ", gradient_start(message));
+    io::stderr().flush()?;
 
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
@@ -363,3 +1099,205 @@ pub fn print_disposition(profile: &BankProfileResponse) {
 
     println!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text_breaks_on_words() {
+        let lines = wrap_text("Alice works at Google on the AI team", 16);
+        assert_eq!(lines, vec!["Alice works at", "Google on the AI", "team"]);
+        assert!(lines.iter().all(|l| l.chars().count() <= 16));
+    }
+
+    #[test]
+    fn test_wrap_text_keeps_line_breaks_and_indent() {
+        let lines = wrap_text("Intro\n\n    code block line here", 16);
+        assert_eq!(lines, vec!["Intro", "", "    code block", "    line here"]);
+    }
+
+    // Drop ANSI escape sequences so rendered output can be compared as plain text
+    fn strip_ansi(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    fn render_plain(text: &str, width: usize) -> Vec<String> {
+        render_markdown(text, width).iter().map(|l| strip_ansi(l)).collect()
+    }
+
+    #[test]
+    fn test_render_markdown_blocks() {
+        let text = "## Summary\n- first point\n2. second point\n```\nlet x = 1;\n```\nDone.";
+        assert_eq!(
+            render_plain(text, 80),
+            vec!["Summary", "• first point", "2. second point", "  let x = 1;", "Done."]
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_list_hanging_indent() {
+        let lines = render_plain("- Alice works at Google on the AI team", 20);
+        assert_eq!(lines, vec!["• Alice works at", "  Google on the AI", "  team"]);
+    }
+
+    #[test]
+    fn test_style_inline() {
+        let styled = style_inline("use **bold** and `code` but not 2 * 3");
+        assert_eq!(strip_ansi(&styled), "use bold and code but not 2 * 3");
+        assert_eq!(style_inline("an **unclosed span"), "an **unclosed span");
+    }
+
+    fn utc(value: &str) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&chrono::Utc)
+    }
+
+    #[test]
+    fn test_object_table() {
+        let items = serde_json::json!([
+            {"fact_id": "f1", "activation": 0.82, "path": ["seed"]},
+            {"fact_id": "f2", "hops": 2}
+        ]);
+        let (headers, rows) = object_table(items.as_array().unwrap()).unwrap();
+        assert_eq!(headers, vec!["activation", "fact_id", "path", "hops"]);
+        assert_eq!(rows[0], vec!["0.82", "f1", "[\"seed\"]", "-"]);
+        assert_eq!(rows[1], vec!["-", "f2", "-", "2"]);
+
+        // Lists of plain values stay on one line
+        assert!(object_table(serde_json::json!(["a", "b"]).as_array().unwrap()).is_none());
+        assert!(object_table(&[]).is_none());
+    }
+
+    #[test]
+    fn test_fit_column() {
+        let headers = ["BANK ID", "MEMORIES"];
+        let mut rows = vec![
+            vec!["notes".to_string(), "12".to_string()],
+            vec!["team-engineering-journal-2024".to_string(), "1840".to_string()],
+        ];
+        // Wide enough already: nothing changes
+        fit_column(&headers, &mut rows, 0, 80);
+        assert_eq!(rows[1][0], "team-engineering-journal-2024");
+
+        fit_column(&headers, &mut rows, 0, 24);
+        assert_eq!(rows[0][0], "notes");
+        assert_eq!(rows[1][0], "team-engineer…");
+        assert!(table_lines(&headers, &rows, &[false, true]).iter().all(|line| line.chars().count() <= 24));
+
+        // Never narrower than the floor
+        fit_column(&headers, &mut rows, 0, 5);
+        assert_eq!(rows[1][0], "team-en…");
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis_multibyte() {
+        assert_eq!(truncate_with_ellipsis("journal-é🔑-bank", 10), "journal-é…");
+        assert_eq!(truncate_with_ellipsis("short", 10), "short");
+    }
+
+    #[test]
+    fn test_table_width_parse() {
+        assert_eq!(TableWidth::parse("AUTO"), Ok(TableWidth::Auto));
+        assert_eq!(TableWidth::parse("100"), Ok(TableWidth::Columns(100)));
+        assert!(TableWidth::parse("10").is_err());
+        assert!(TableWidth::parse("wide").is_err());
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let rows = vec![
+            vec!["notes".to_string(), "12".to_string()],
+            vec!["work-journal".to_string(), "1840".to_string()],
+        ];
+        assert_eq!(
+            table_lines(&["BANK ID", "MEMORIES"], &rows, &[false, true]),
+            vec![
+                "BANK ID       MEMORIES",
+                "notes               12",
+                "work-journal      1840",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        use std::time::Duration;
+        assert_eq!(format_elapsed(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_elapsed(Duration::from_millis(2350)), "2.35s");
+        assert_eq!(format_elapsed(Duration::from_secs(65)), "1m 05s");
+    }
+
+    #[test]
+    fn test_relative_time() {
+        let now = utc("2024-06-15T12:00:00Z");
+        assert_eq!(relative_time(utc("2024-06-15T11:59:30Z"), now).as_deref(), Some("just now"));
+        assert_eq!(relative_time(utc("2024-06-15T11:55:00Z"), now).as_deref(), Some("5 minutes ago"));
+        assert_eq!(relative_time(utc("2024-06-15T11:00:00Z"), now).as_deref(), Some("1 hour ago"));
+        assert_eq!(relative_time(utc("2024-06-12T09:00:00Z"), now).as_deref(), Some("3 days ago"));
+        assert_eq!(relative_time(utc("2024-06-17T12:00:00Z"), now).as_deref(), Some("in 2 days"));
+        assert_eq!(relative_time(utc("2024-04-01T00:00:00Z"), now), None);
+    }
+
+    #[test]
+    fn test_format_fact_date_keeps_unparseable_values() {
+        assert_eq!(format_fact_date("sometime in spring"), "sometime in spring");
+        assert_eq!(format_fact_date("2001-01-01T00:00:00Z"), "2001-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_group_by_type_keeps_order() {
+        let types = vec![Some("world"), Some("opinion"), None, Some("world"), Some("opinion")];
+        assert_eq!(
+            group_by_type(types.into_iter()),
+            vec![("world", vec![0, 3]), ("opinion", vec![1, 4]), ("unknown", vec![2])]
+        );
+    }
+
+    #[test]
+    fn test_query_terms_lowercase_and_dedup() {
+        assert_eq!(query_terms("  Alice likes ALICE  coffee "), vec!["alice", "likes", "coffee"]);
+    }
+
+    #[test]
+    fn test_highlight_terms_case_insensitive() {
+        let terms = query_terms("alice");
+        let line = highlight_terms("Alice met ALICE", &terms);
+        assert_eq!(strip_ansi(&line), "Alice met ALICE");
+        assert_eq!(highlight_terms("Bob met Carol", &terms), "Bob met Carol");
+    }
+
+    #[test]
+    fn test_match_len_multibyte() {
+        assert_eq!(match_len("Café au lait", "café"), Some("Café".len()));
+        assert_eq!(match_len("ÜBER alles", "über"), Some("ÜBER".len()));
+        assert_eq!(match_len("caf", "café"), None);
+        assert_eq!(match_len("日本語です", "日本"), Some("日本".len()));
+        assert_eq!(match_len("anything", ""), None);
+    }
+
+    #[test]
+    fn test_highlight_terms_keeps_multibyte_text_intact() {
+        let terms = query_terms("本");
+        let line = highlight_terms("日本語", &terms);
+        assert_eq!(strip_ansi(&line), "日本語");
+    }
+
+    #[test]
+    fn test_wrap_text_long_word_gets_own_line() {
+        let lines = wrap_text("see https://example.com/a/very/long/path now", 10);
+        assert_eq!(lines, vec!["see", "https://example.com/a/very/long/path", "now"]);
+    }
+}
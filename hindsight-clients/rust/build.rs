@@ -107,8 +107,14 @@ fn main() {
     let spec: openapiv3::OpenAPI = serde_json::from_value(spec_json)
         .expect("Failed to parse converted OpenAPI spec");
 
-    // Generate the client
-    let mut generator = progenitor::Generator::default();
+    // Generate the client. Every request goes through `add_request_id` (lib.rs), which
+    // sets X-Request-Id for requests made inside `with_request_id`. The hook needs an
+    // inner type; lib.rs wraps the client so `Client::new(url)` doesn't take one.
+    let mut settings = progenitor::GenerationSettings::default();
+    settings
+        .with_inner_type(quote::quote!(()))
+        .with_pre_hook_async(quote::quote!(crate::add_request_id));
+    let mut generator = progenitor::Generator::new(&settings);
 
     // Generate code
     let tokens = generator.generate_tokens(&spec)
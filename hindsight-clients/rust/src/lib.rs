@@ -19,8 +19,59 @@
 //! }
 //! ```
 
-// Include the generated client code (which already exports Error and ResponseValue)
-include!(concat!(env!("OUT_DIR"), "/hindsight_client_generated.rs"));
+// The generated client code (which already exports Error and ResponseValue). Its
+// client takes an inner `()` for the request ID hook, so `Client` below wraps it.
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/hindsight_client_generated.rs"));
+}
+pub use generated::*;
+
+/// Client for the Hindsight API. Every API method of the generated client is
+/// available on it.
+#[derive(Clone, Debug)]
+pub struct Client(generated::Client);
+
+impl Client {
+    /// Create a client for the API at `baseurl`
+    pub fn new(baseurl: &str) -> Self {
+        Self(generated::Client::new(baseurl, ()))
+    }
+
+    /// Create a client using a preconfigured `reqwest::Client` (timeouts, headers, TLS)
+    pub fn new_with_client(baseurl: &str, client: reqwest::Client) -> Self {
+        Self(generated::Client::new_with_client(baseurl, client, ()))
+    }
+}
+
+impl std::ops::Deref for Client {
+    type Target = generated::Client;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Run `future` with every request it sends carrying `X-Request-Id: request_id`, so
+/// server logs can tie them to one call (e.g. the retries of a single request).
+pub async fn with_request_id<F: std::future::Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+// Pre-request hook installed by build.rs
+async fn add_request_id(
+    _inner: &(),
+    request: &mut reqwest::Request,
+) -> Result<(), reqwest::header::InvalidHeaderValue> {
+    let Ok(value) = REQUEST_ID.try_with(|id| reqwest::header::HeaderValue::from_str(id)) else {
+        return Ok(());
+    };
+    request.headers_mut().insert("x-request-id", value?);
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {